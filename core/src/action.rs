@@ -1,9 +1,15 @@
-use agent::state::AgentState;
+use agent::state::{AgentState, EntryUpdate};
 use context::Context;
-use hash_table::entry::Entry;
+use hash_table::{
+    entry::Entry,
+    links::{Link, LinkQuery},
+};
 use holochain_dna::Dna;
 use instance::Observer;
-use nucleus::{state::NucleusState, EntrySubmission, FunctionCall, FunctionResult};
+use nucleus::{
+    state::NucleusState, EntrySubmission, FunctionCall, FunctionResult, LinkSubmission,
+    LinkValidationResult, ValidationResult,
+};
 use snowflake;
 use std::{
     hash::{Hash, Hasher},
@@ -66,8 +72,31 @@ pub enum Action {
     /// entry to Commit
     /// MUST already have passed all callback checks
     Commit(Entry),
+    /// entry that failed validation
+    /// recorded with a REJECTED status rather than committed to the source chain
+    Reject(Entry),
     /// hash to Get
     Get(String),
+    /// new version of an entry on the source chain to push
+    /// MUST already have passed all callback checks
+    UpdateEntry(EntryUpdate),
+    /// hash of an entry on the source chain to remove with a deletion record
+    /// MUST already have passed all callback checks
+    RemoveEntry(String),
+
+    /// link to add from an entry on the source chain
+    /// MUST already have passed links_to validation
+    AddLink(Link),
+    /// link to remove from an entry on the source chain with a tombstone
+    RemoveLink(Link),
+    /// links to Get from an entry on the source chain
+    GetLinks(LinkQuery),
+
+    /// public key of the new keys to replace the current agent keys with
+    /// the keys themselves are added to the context with Context::add_rotation_keys() so the
+    /// private key never travels through actions
+    /// the rotation is recorded on the source chain and everything after it is signed by them
+    RotateKeys(String),
 
     /// execute a function in a zome WASM
     ExecuteZomeFunction(FunctionCall),
@@ -81,9 +110,16 @@ pub enum Action {
     /// return the result of an InitApplication action
     ReturnInitializationResult(Option<String>),
 
-    /// ???
+    /// execute the validation WASM of an entry type against an entry
     // @TODO how does this relate to validating a commit?
     ValidateEntry(EntrySubmission),
+    /// return the result of a ValidateEntry action
+    ReturnValidationResult(ValidationResult),
+
+    /// execute the validation WASM of the links_to of an entry type against a link
+    ValidateLink(LinkSubmission),
+    /// return the result of a ValidateLink action
+    ReturnLinkValidationResult(LinkValidationResult),
 
     /// add a network peer
     AddPeer(String),
@@ -0,0 +1,195 @@
+use agent::keys::Keys;
+use crypto::{
+    aead::{AeadDecryptor, AeadEncryptor},
+    chacha20poly1305::ChaCha20Poly1305,
+    digest::Digest,
+    hkdf::{hkdf_expand, hkdf_extract},
+    sha2::Sha256,
+};
+use error::HolochainError;
+use hash::canonical_json;
+use hash_table::entry::Entry;
+use rand::{OsRng, Rng};
+use rust_base58::{FromBase58, ToBase58};
+use serde_json;
+
+/// length in bytes of the key derived from the agent keys
+const KEY_LENGTH: usize = 32;
+/// length in bytes of the random nonce every entry is encrypted with, as for XChaCha20
+/// the nonce derives a key for the single entry so nonces are too long to collide
+const NONCE_LENGTH: usize = 24;
+/// chacha20poly1305 nonce, always the same as every derived key encrypts a single entry
+const CIPHER_NONCE: [u8; 8] = [0; 8];
+/// length in bytes of the poly1305 tag
+const TAG_LENGTH: usize = 16;
+/// binds the keys derived from agent keys to entry encryption
+const KEY_INFO: &str = "holochain entry encryption";
+
+/// the content of an entry of an Encrypted entry type as it is stored in the hash table
+/// key = the base58 public key of the agent keys that encrypted the content
+/// nonce, ciphertext and tag are base58 encoded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct EncryptedContent {
+    key: String,
+    nonce: String,
+    ciphertext: String,
+    tag: String,
+}
+
+/// derives the symmetric key that encrypts a single entry from the agent keys and its nonce
+fn derive_key(keys: &Keys, nonce: &[u8]) -> Vec<u8> {
+    let digest = Sha256::new();
+    let mut prk = vec![0; digest.output_bytes()];
+    hkdf_extract(digest, nonce, &keys.private_key().bytes(), &mut prk);
+    let mut key = vec![0; KEY_LENGTH];
+    hkdf_expand(Sha256::new(), &prk, KEY_INFO.as_bytes(), &mut key);
+    key
+}
+
+/// decodes a base58 field of encrypted content, checking its length if there is one
+fn decode_field(
+    field: &str,
+    value: &str,
+    length: Option<usize>,
+) -> Result<Vec<u8>, HolochainError> {
+    match value.from_base58() {
+        Ok(ref bytes) if length.is_none() || length == Some(bytes.len()) => Ok(bytes.clone()),
+        _ => Err(HolochainError::new(&format!(
+            "encrypted entry has an invalid {}",
+            field
+        ))),
+    }
+}
+
+/// encrypts the content of an entry with a key derived from the agent keys
+/// the encrypted entry has the same type and can only be decrypted with the same keys
+pub fn encrypt_entry(keys: &Keys, entry: &Entry) -> Result<Entry, HolochainError> {
+    let mut nonce = vec![0; NONCE_LENGTH];
+    OsRng::new()?.fill_bytes(&mut nonce);
+
+    let plaintext = entry.content().into_bytes();
+    let mut ciphertext = vec![0; plaintext.len()];
+    let mut tag = vec![0; TAG_LENGTH];
+    // the entry type is authenticated too so encrypted content can't be moved to another type
+    ChaCha20Poly1305::new(
+        &derive_key(keys, &nonce),
+        &CIPHER_NONCE,
+        entry.entry_type().as_bytes(),
+    ).encrypt(&plaintext, &mut ciphertext, &mut tag);
+
+    let content = EncryptedContent {
+        key: keys.public_key().to_b58(),
+        nonce: nonce.to_base58(),
+        ciphertext: ciphertext.to_base58(),
+        tag: tag.to_base58(),
+    };
+    Ok(Entry::new(&entry.entry_type(), &canonical_json(&content)))
+}
+
+/// parses the content of an entry encrypted by encrypt_entry()
+fn encrypted_content(entry: &Entry) -> Result<EncryptedContent, HolochainError> {
+    serde_json::from_str(&entry.content())
+        .map_err(|err| HolochainError::new(&format!("entry is not encrypted: {}", err)))
+}
+
+/// the base58 public key of the agent keys that encrypted an entry
+/// entries encrypted before the agent rotated its keys must be decrypted by the retired keys
+pub fn encrypted_by(entry: &Entry) -> Result<String, HolochainError> {
+    Ok(encrypted_content(entry)?.key)
+}
+
+/// decrypts an entry encrypted by encrypt_entry() with the same agent keys
+/// @see encrypted_by()
+pub fn decrypt_entry(keys: &Keys, entry: &Entry) -> Result<Entry, HolochainError> {
+    let content = encrypted_content(entry)?;
+    if content.key != keys.public_key().to_b58() {
+        return Err(HolochainError::new(&format!(
+            "entry is encrypted by {} not {}",
+            content.key,
+            keys.public_key().to_b58(),
+        )));
+    }
+
+    let nonce = decode_field("nonce", &content.nonce, Some(NONCE_LENGTH))?;
+    let ciphertext = decode_field("ciphertext", &content.ciphertext, None)?;
+    let tag = decode_field("tag", &content.tag, Some(TAG_LENGTH))?;
+
+    let mut plaintext = vec![0; ciphertext.len()];
+    if !ChaCha20Poly1305::new(
+        &derive_key(keys, &nonce),
+        &CIPHER_NONCE,
+        entry.entry_type().as_bytes(),
+    ).decrypt(&ciphertext, &mut plaintext, &tag)
+    {
+        return Err(HolochainError::new(
+            "encrypted entry could not be decrypted",
+        ));
+    }
+
+    let plaintext = String::from_utf8(plaintext)
+        .map_err(|_| HolochainError::new("decrypted entry content is not valid UTF-8"))?;
+    Ok(Entry::new(&entry.entry_type(), &plaintext))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{decrypt_entry, encrypt_entry, encrypted_by, EncryptedContent, NONCE_LENGTH};
+    use agent::keys::tests::{test_keys, test_keys_b};
+    use hash::canonical_json;
+    use hash_table::entry::{tests::test_entry, Entry};
+    use rust_base58::FromBase58;
+    use serde_json;
+
+    #[test]
+    /// test that entries round trip through encryption
+    fn round_trip() {
+        let encrypted =
+            encrypt_entry(&test_keys(), &test_entry()).expect("encryption shouldn't fail");
+        assert_eq!(test_entry().entry_type(), encrypted.entry_type());
+        assert!(!encrypted.content().contains(&test_entry().content()));
+
+        let decrypted = decrypt_entry(&test_keys(), &encrypted).expect("decryption shouldn't fail");
+        assert_eq!(test_entry(), decrypted);
+        assert_eq!(test_entry().entry_type(), decrypted.entry_type());
+
+        // every encryption has a new nonce
+        let again = encrypt_entry(&test_keys(), &test_entry()).expect("encryption shouldn't fail");
+        assert_ne!(encrypted.content(), again.content());
+        let content: EncryptedContent = serde_json::from_str(&encrypted.content()).unwrap();
+        assert_eq!(NONCE_LENGTH, content.nonce.from_base58().unwrap().len());
+
+        // the entry records the keys to decrypt it with
+        assert_eq!(
+            Ok(test_keys().public_key().to_b58()),
+            encrypted_by(&encrypted)
+        );
+        assert!(encrypted_by(&test_entry()).is_err());
+    }
+
+    #[test]
+    /// test that only the keys that encrypted an entry can decrypt it
+    fn decrypt_errors() {
+        let encrypted =
+            encrypt_entry(&test_keys(), &test_entry()).expect("encryption shouldn't fail");
+        assert!(decrypt_entry(&test_keys_b(), &encrypted).is_err());
+
+        // content that isn't encrypted
+        assert!(decrypt_entry(&test_keys(), &test_entry()).is_err());
+
+        // the entry type is authenticated
+        let moved = Entry::new("otherEntryType", &encrypted.content());
+        assert!(decrypt_entry(&test_keys(), &moved).is_err());
+
+        // claiming to be encrypted by other keys doesn't help them decrypt
+        let mut content: EncryptedContent = serde_json::from_str(&encrypted.content()).unwrap();
+        content.key = test_keys_b().public_key().to_b58();
+        let claimed = Entry::new(&encrypted.entry_type(), &canonical_json(&content));
+        assert!(decrypt_entry(&test_keys_b(), &claimed).is_err());
+
+        // a bad nonce is an error, not a panic
+        let mut content: EncryptedContent = serde_json::from_str(&encrypted.content()).unwrap();
+        content.nonce = "1".into();
+        let bad_nonce = Entry::new(&encrypted.entry_type(), &canonical_json(&content));
+        assert!(decrypt_entry(&test_keys(), &bad_nonce).is_err());
+    }
+}
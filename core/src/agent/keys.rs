@@ -1,35 +1,147 @@
-#[derive(Clone, Debug, PartialEq, Default)]
+use crypto::ed25519;
+use error::HolochainError;
+use hash::{bytes_to_b58_hash, DEFAULT_HASH};
+use rand::{OsRng, Rng};
+use rust_base58::{FromBase58, ToBase58};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+/// length in bytes of the seed an ed25519 keypair is derived from
+pub const SEED_LENGTH: usize = 32;
+/// length in bytes of an ed25519 public key
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+/// length in bytes of an ed25519 private key (secret scalar + public key)
+pub const PRIVATE_KEY_LENGTH: usize = 64;
+/// length in bytes of an ed25519 signature
+pub const SIGNATURE_LENGTH: usize = 64;
+
+#[derive(Clone, PartialEq, Hash, Default)]
 /// represents a single Key
 /// e.g. private + public keys would be two Key structs
-pub struct Key {}
+pub struct Key {
+    bytes: Vec<u8>,
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // only public keys are printed, anything else could be private key material
+        if self.bytes.len() == PUBLIC_KEY_LENGTH {
+            write!(f, "Key({})", self.to_b58())
+        } else {
+            write!(f, "Key(<{} bytes>)", self.bytes.len())
+        }
+    }
+}
 
 impl Key {
-    /// returns a new agent Key
-    pub fn new() -> Key {
-        Key {}
+    /// returns a new agent Key wrapping the given key material
+    pub fn new(bytes: &[u8]) -> Key {
+        Key {
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    /// getter for a copy of the raw key material
+    pub fn bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// the key material as a base58 string
+    pub fn to_b58(&self) -> String {
+        self.bytes.to_base58()
+    }
+
+    /// restores a Key from base58 key material
+    pub fn from_b58(s: &str) -> Result<Key, HolochainError> {
+        s.from_base58()
+            .map(|bytes| Key::new(&bytes))
+            .map_err(|_| HolochainError::new(&format!("invalid base58 key: {}", s)))
+    }
+
+    /// the node id of the agent holding this public key, the multihash of the key material
+    pub fn node_id(&self) -> String {
+        bytes_to_b58_hash(&self.bytes, DEFAULT_HASH)
+    }
+
+    /// true if the base58 signature is a valid signature of data for this public key
+    pub fn verify(&self, data: &[u8], signature: &str) -> bool {
+        match signature.from_base58() {
+            Ok(ref signature) if signature.len() == SIGNATURE_LENGTH => {
+                self.bytes.len() == PUBLIC_KEY_LENGTH
+                    && ed25519::verify(data, &self.bytes, signature)
+            }
+            _ => false,
+        }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Default)]
 /// represents a set of Keys for an agent
 /// includes both public and private keys
-/// also includes the node id of the agent with these keys
+/// also includes the node id of the agent with these keys, derived from the public key
 pub struct Keys {
     public_key: Key,
     private_key: Key,
     node_id: String,
+    /// logical clock for everything asserted with these keys
+    /// clones of the keys share the same clock
+    txn: Arc<Mutex<u64>>,
+}
+
+impl fmt::Debug for Keys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the private key must never end up in logs, e.g. through {:?} of the agent state
+        write!(
+            f,
+            "Keys {{ public_key: {:?}, node_id: {:?} }}",
+            self.public_key, self.node_id
+        )
+    }
+}
+
+impl PartialEq for Keys {
+    fn eq(&self, other: &Keys) -> bool {
+        // the clock is state, not identity
+        self.public_key == other.public_key
+            && self.private_key == other.private_key
+            && self.node_id == other.node_id
+    }
+}
+
+impl Hash for Keys {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // keys are identified by their public key
+        self.public_key.hash(state);
+    }
 }
 
 impl Keys {
     /// returns a new set of agent Keys
-    pub fn new<S: Into<String>>(public_key: &Key, private_key: &Key, node_id: S) -> Keys {
+    pub fn new(public_key: &Key, private_key: &Key) -> Keys {
         Keys {
             public_key: public_key.clone(),
             private_key: private_key.clone(),
-            node_id: node_id.into(),
+            node_id: public_key.node_id(),
+            txn: Arc::new(Mutex::new(0)),
         }
     }
 
+    /// derives an ed25519 keypair deterministically from a seed
+    pub fn from_seed(seed: &[u8; SEED_LENGTH]) -> Keys {
+        let (private_key, public_key) = ed25519::keypair(seed);
+        Keys::new(&Key::new(&public_key), &Key::new(&private_key))
+    }
+
+    /// generates a new ed25519 keypair from a random seed, e.g. for a new agent
+    pub fn generate() -> Result<Keys, HolochainError> {
+        let mut seed = [0; SEED_LENGTH];
+        OsRng::new()?.fill_bytes(&mut seed);
+        Ok(Keys::from_seed(&seed))
+    }
+
     /// getter for the public key
     pub fn public_key(&self) -> Key {
         self.public_key.clone()
@@ -44,36 +156,91 @@ impl Keys {
     pub fn node_id(&self) -> String {
         self.node_id.clone()
     }
+
+    /// signs data with the private key and returns the base58 signature
+    /// anyone can check the signature with the public key, @see Key::verify()
+    ///
+    /// # Panics
+    ///
+    /// Panics if the private key isn't an ed25519 private key
+    pub fn sign(&self, data: &[u8]) -> String {
+        assert_eq!(
+            PRIVATE_KEY_LENGTH,
+            self.private_key.bytes.len(),
+            "attempted to sign with an invalid private key"
+        );
+        ed25519::signature(data, &self.private_key.bytes).to_base58()
+    }
+
+    /// true if the base58 signature is a valid signature of data by these keys
+    pub fn verify(&self, data: &[u8], signature: &str) -> bool {
+        self.public_key.verify(data, signature)
+    }
+
+    /// ticks the logical clock and returns the new value
+    /// every call returns a value greater than all previous calls for these keys and their clones
+    pub fn next_txn(&self) -> u64 {
+        let mut txn = self
+            .txn
+            .lock()
+            .expect("owners of the txn Mutex shouldn't panic");
+        *txn += 1;
+        *txn
+    }
+
+    /// moves the logical clock on to at least txn so every later call to next_txn() is greater
+    /// the clock starts at 0 for new or loaded keys, so txns already asserted with the keys
+    /// before a restart must be observed before asserting anything new
+    pub fn observe_txn(&self, txn: u64) {
+        let mut current = self
+            .txn
+            .lock()
+            .expect("owners of the txn Mutex shouldn't panic");
+        if txn > *current {
+            *current = txn;
+        }
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
 
-    use super::{Key, Keys};
+    use super::{Key, Keys, PRIVATE_KEY_LENGTH, PUBLIC_KEY_LENGTH, SEED_LENGTH};
+    use hash::{algorithm_of, DEFAULT_HASH};
+
+    /// seed for the keys used in tests
+    pub fn test_seed() -> [u8; SEED_LENGTH] {
+        [1; SEED_LENGTH]
+    }
 
     /// generates a new key suitable for testing
     pub fn test_key() -> Key {
-        Key::new()
+        test_public_key()
     }
 
     /// dummy public key
     pub fn test_public_key() -> Key {
-        test_key()
+        test_keys().public_key()
     }
 
     /// dummy private key
     pub fn test_private_key() -> Key {
-        test_key()
+        test_keys().private_key()
     }
 
-    /// generates a new node id suitable for testing
+    /// the node id of test_keys()
     pub fn test_node_id() -> String {
-        "test node id".into()
+        test_keys().node_id()
     }
 
     /// generates new id/pub/priv keys suitable for testing
     pub fn test_keys() -> Keys {
-        Keys::new(&test_key(), &test_key(), test_node_id())
+        Keys::from_seed(&test_seed())
+    }
+
+    /// generates keys for testing that differ from test_keys()
+    pub fn test_keys_b() -> Keys {
+        Keys::from_seed(&[2; SEED_LENGTH])
     }
 
     #[test]
@@ -82,12 +249,32 @@ pub mod tests {
         test_key();
     }
 
+    #[test]
+    /// tests key b58 round trip
+    fn key_b58() {
+        let key = test_key();
+        assert_eq!(Ok(key.clone()), Key::from_b58(&key.to_b58()));
+        assert!(Key::from_b58("not b58!").is_err());
+    }
+
     #[test]
     /// smoke test new keys
     fn keys_new() {
         test_keys();
     }
 
+    #[test]
+    /// tests Keys::from_seed()
+    fn keys_from_seed() {
+        let keys = test_keys();
+        assert_eq!(PUBLIC_KEY_LENGTH, keys.public_key().bytes().len());
+        assert_eq!(PRIVATE_KEY_LENGTH, keys.private_key().bytes().len());
+
+        // the same seed always derives the same keys
+        assert_eq!(test_keys(), keys);
+        assert_ne!(test_keys_b().public_key(), keys.public_key());
+    }
+
     #[test]
     /// tests keys.public_key()
     fn keys_public_key() {
@@ -100,4 +287,101 @@ pub mod tests {
         assert_eq!(test_keys().private_key(), test_private_key());
     }
 
+    #[test]
+    /// tests signing and verifying
+    fn keys_sign_verify() {
+        let keys = test_keys();
+        let signature = keys.sign(b"some data");
+
+        assert!(keys.public_key().verify(b"some data", &signature));
+        // wrong data
+        assert!(!keys.public_key().verify(b"other data", &signature));
+        // wrong key
+        assert!(!test_keys_b().public_key().verify(b"some data", &signature));
+        // garbage signatures
+        assert!(!keys.public_key().verify(b"some data", ""));
+        assert!(!keys.public_key().verify(b"some data", "not b58!"));
+        assert!(!Key::new(&[]).verify(b"some data", &signature));
+    }
+
+    #[test]
+    #[should_panic]
+    /// signing needs a real private key
+    fn keys_sign_invalid() {
+        Keys::new(&Key::new(&[]), &Key::new(&[])).sign(b"some data");
+    }
+
+    #[test]
+    /// tests Keys::generate()
+    fn keys_generate() {
+        let keys = Keys::generate().expect("generating keys shouldn't fail");
+        assert_eq!(PUBLIC_KEY_LENGTH, keys.public_key().bytes().len());
+        assert_eq!(PRIVATE_KEY_LENGTH, keys.private_key().bytes().len());
+
+        // generated keys can sign
+        let signature = keys.sign(b"some data");
+        assert!(keys.verify(b"some data", &signature));
+
+        // every agent gets a different keypair
+        let other = Keys::generate().expect("generating keys shouldn't fail");
+        assert_ne!(keys.public_key(), other.public_key());
+        assert_ne!(keys.node_id(), other.node_id());
+    }
+
+    #[test]
+    /// tests that the node id is derived from the public key
+    fn keys_node_id() {
+        let keys = test_keys();
+        assert_eq!(test_node_id(), keys.node_id());
+        assert_eq!(keys.public_key().node_id(), keys.node_id());
+        assert_eq!(Some(DEFAULT_HASH), algorithm_of(&keys.node_id()));
+        assert_ne!(test_keys_b().node_id(), keys.node_id());
+    }
+
+    #[test]
+    /// tests keys.next_txn()
+    fn keys_next_txn() {
+        let keys = test_keys();
+        assert_eq!(1, keys.next_txn());
+        assert_eq!(2, keys.next_txn());
+
+        // clones share the clock
+        let cloned = keys.clone();
+        assert_eq!(3, cloned.next_txn());
+        assert_eq!(4, keys.next_txn());
+
+        // the clock doesn't affect equality
+        assert_eq!(test_keys(), keys);
+    }
+
+    #[test]
+    /// tests that debugging keys never prints the private key
+    fn keys_debug() {
+        let keys = test_keys();
+        let private_key = keys.private_key().to_b58();
+        for debug in &[format!("{:?}", keys), format!("{:?}", keys.private_key())] {
+            assert!(
+                !debug.contains(&private_key),
+                "{} leaks the private key",
+                debug
+            );
+        }
+        assert!(format!("{:?}", keys).contains(&keys.public_key().to_b58()));
+        assert_eq!(
+            format!("Key({})", keys.public_key().to_b58()),
+            format!("{:?}", keys.public_key())
+        );
+    }
+
+    #[test]
+    /// tests keys.observe_txn()
+    fn keys_observe_txn() {
+        let keys = test_keys();
+        keys.observe_txn(5);
+        assert_eq!(6, keys.next_txn());
+
+        // the clock never goes back
+        keys.observe_txn(3);
+        assert_eq!(7, keys.next_txn());
+    }
 }
@@ -0,0 +1,481 @@
+use agent::keys::{Key, Keys, PRIVATE_KEY_LENGTH, PUBLIC_KEY_LENGTH};
+use crypto::{
+    aead::{AeadDecryptor, AeadEncryptor},
+    chacha20poly1305::ChaCha20Poly1305,
+    scrypt::{scrypt, ScryptParams},
+};
+use error::HolochainError;
+use holochain_agent::Agent;
+use rand::{OsRng, Rng};
+use rust_base58::{FromBase58, ToBase58};
+use serde_json;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// version of the keystore file format
+pub const KEYSTORE_VERSION: u32 = 1;
+/// default log2 of the scrypt N parameter used to derive keys from passphrases
+pub const DEFAULT_WORK_FACTOR: u8 = 14;
+/// scrypt r parameter
+const SCRYPT_R: u32 = 8;
+/// scrypt p parameter
+const SCRYPT_P: u32 = 1;
+/// largest work factor accepted from a file, anything bigger is treated as corruption
+const MAX_WORK_FACTOR: u8 = 20;
+/// length in bytes of the random salt for each passphrase derived key
+const SALT_LENGTH: usize = 16;
+/// length in bytes of the key derived from a passphrase
+const DERIVED_KEY_LENGTH: usize = 32;
+/// length in bytes of the chacha20poly1305 nonce
+const NONCE_LENGTH: usize = 8;
+/// length in bytes of the poly1305 tag
+const TAG_LENGTH: usize = 16;
+
+/// a private key encrypted with a key derived from a passphrase
+/// the public key is kept in the clear so identities can be listed without a passphrase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct EncryptedIdentity {
+    public_key: String,
+    work_factor: u8,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+    tag: String,
+}
+
+/// everything in a keystore file
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct KeystoreFile {
+    version: u32,
+    identities: BTreeMap<String, EncryptedIdentity>,
+}
+
+/// a file holding any number of named agent identities
+/// every identity is encrypted separately with its own passphrase so identities can be added,
+/// removed and loaded independently
+/// every change is written to disk immediately
+#[derive(Debug, PartialEq)]
+pub struct Keystore {
+    path: PathBuf,
+    work_factor: u8,
+    identities: BTreeMap<String, EncryptedIdentity>,
+}
+
+/// random bytes from the OS
+fn random_bytes(length: usize) -> Result<Vec<u8>, HolochainError> {
+    let mut bytes = vec![0; length];
+    OsRng::new()?.fill_bytes(&mut bytes);
+    Ok(bytes)
+}
+
+/// derives a symmetric key from a passphrase
+fn derive_key(passphrase: &str, salt: &[u8], work_factor: u8) -> Vec<u8> {
+    let mut key = vec![0; DERIVED_KEY_LENGTH];
+    scrypt(
+        passphrase.as_bytes(),
+        salt,
+        &ScryptParams::new(work_factor, SCRYPT_R, SCRYPT_P),
+        &mut key,
+    );
+    key
+}
+
+/// decodes a base58 field of a stored identity, checking its length
+fn decode_field(
+    name: &str,
+    field: &str,
+    value: &str,
+    length: usize,
+) -> Result<Vec<u8>, HolochainError> {
+    match value.from_base58() {
+        Ok(ref bytes) if bytes.len() == length => Ok(bytes.clone()),
+        _ => Err(HolochainError::CorruptKeystore(format!(
+            "identity {} has an invalid {}",
+            name, field
+        ))),
+    }
+}
+
+impl Keystore {
+    /// opens the keystore at the given path, an empty keystore if the file doesn't exist yet
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Keystore, HolochainError> {
+        let path = path.as_ref().to_path_buf();
+        let identities = if path.is_file() {
+            let file: KeystoreFile =
+                serde_json::from_str(&fs::read_to_string(&path)?).map_err(|err| {
+                    HolochainError::CorruptKeystore(format!(
+                        "keystore {} is not a keystore: {}",
+                        path.display(),
+                        err
+                    ))
+                })?;
+            if file.version != KEYSTORE_VERSION {
+                return Err(HolochainError::CorruptKeystore(format!(
+                    "keystore {} has unsupported version {}",
+                    path.display(),
+                    file.version
+                )));
+            }
+            file.identities
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Keystore {
+            path,
+            work_factor: DEFAULT_WORK_FACTOR,
+            identities,
+        })
+    }
+
+    /// getter for a copy of the keystore file path
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// sets the log2 of the scrypt N parameter for identities added from now on
+    /// higher is slower to brute force and slower to add/load, existing identities keep theirs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the work factor is 0 or bigger than the keystore accepts when loading
+    pub fn set_work_factor(&mut self, work_factor: u8) {
+        assert!(
+            work_factor > 0 && work_factor <= MAX_WORK_FACTOR,
+            "work factor must be between 1 and {}",
+            MAX_WORK_FACTOR
+        );
+        self.work_factor = work_factor;
+    }
+
+    /// the names of every identity in the keystore, in order
+    pub fn identities(&self) -> Vec<String> {
+        self.identities.keys().cloned().collect()
+    }
+
+    /// the public key of an identity, no passphrase needed
+    pub fn public_key(&self, name: &str) -> Result<Key, HolochainError> {
+        let identity = self.identity(name)?;
+        Ok(Key::new(&decode_field(
+            name,
+            "public key",
+            &identity.public_key,
+            PUBLIC_KEY_LENGTH,
+        )?))
+    }
+
+    /// encrypts the keys with the passphrase and stores them under the given name
+    /// names are unique, remove() an identity before replacing it
+    pub fn add(&mut self, name: &str, keys: &Keys, passphrase: &str) -> Result<(), HolochainError> {
+        if self.identities.contains_key(name) {
+            return Err(HolochainError::new(&format!(
+                "keystore already has an identity named {}",
+                name
+            )));
+        }
+        let private_key = keys.private_key().bytes();
+        // an ed25519 private key ends with its public key
+        if keys.public_key().bytes().len() != PUBLIC_KEY_LENGTH
+            || private_key.len() != PRIVATE_KEY_LENGTH
+            || private_key[PRIVATE_KEY_LENGTH - PUBLIC_KEY_LENGTH..]
+                != keys.public_key().bytes()[..]
+        {
+            return Err(HolochainError::new(&format!(
+                "the keys for identity {} are not a valid keypair",
+                name
+            )));
+        }
+
+        let salt = random_bytes(SALT_LENGTH)?;
+        let nonce = random_bytes(NONCE_LENGTH)?;
+        let key = derive_key(passphrase, &salt, self.work_factor);
+        let public_key = keys.public_key().to_b58();
+        // the name and public key are authenticated too so stored identities can't be swapped
+        let aad = Self::aad(name, &public_key);
+
+        let mut ciphertext = vec![0; PRIVATE_KEY_LENGTH];
+        let mut tag = vec![0; TAG_LENGTH];
+        let mut cipher = ChaCha20Poly1305::new(&key, &nonce, aad.as_bytes());
+        cipher.encrypt(&private_key, &mut ciphertext, &mut tag);
+
+        self.identities.insert(
+            name.to_string(),
+            EncryptedIdentity {
+                public_key,
+                work_factor: self.work_factor,
+                salt: salt.to_base58(),
+                nonce: nonce.to_base58(),
+                ciphertext: ciphertext.to_base58(),
+                tag: tag.to_base58(),
+            },
+        );
+        self.save()
+    }
+
+    /// removes an identity from the keystore
+    pub fn remove(&mut self, name: &str) -> Result<(), HolochainError> {
+        self.identity(name)?;
+        self.identities.remove(name);
+        self.save()
+    }
+
+    /// decrypts an identity with its passphrase
+    /// the agent is named after the identity
+    pub fn load(&self, name: &str, passphrase: &str) -> Result<(Agent, Keys), HolochainError> {
+        let identity = self.identity(name)?;
+        let public_key = self.public_key(name)?;
+        let salt = decode_field(name, "salt", &identity.salt, SALT_LENGTH)?;
+        let nonce = decode_field(name, "nonce", &identity.nonce, NONCE_LENGTH)?;
+        let ciphertext =
+            decode_field(name, "ciphertext", &identity.ciphertext, PRIVATE_KEY_LENGTH)?;
+        let tag = decode_field(name, "tag", &identity.tag, TAG_LENGTH)?;
+        if identity.work_factor == 0 || identity.work_factor > MAX_WORK_FACTOR {
+            return Err(HolochainError::CorruptKeystore(format!(
+                "identity {} has an invalid work factor",
+                name
+            )));
+        }
+
+        let key = derive_key(passphrase, &salt, identity.work_factor);
+        let aad = Self::aad(name, &identity.public_key);
+        let mut private_key = vec![0; PRIVATE_KEY_LENGTH];
+        let mut cipher = ChaCha20Poly1305::new(&key, &nonce, aad.as_bytes());
+        if !cipher.decrypt(&ciphertext, &mut private_key, &tag) {
+            return Err(HolochainError::WrongPassphrase(format!(
+                "wrong passphrase for identity {}",
+                name
+            )));
+        }
+
+        // an ed25519 private key ends with its public key
+        if private_key[PRIVATE_KEY_LENGTH - PUBLIC_KEY_LENGTH..] != public_key.bytes()[..] {
+            return Err(HolochainError::CorruptKeystore(format!(
+                "identity {} has a private key that doesn't match its public key",
+                name
+            )));
+        }
+
+        Ok((
+            Agent::from_string(name.to_string()),
+            Keys::new(&public_key, &Key::new(&private_key)),
+        ))
+    }
+
+    /// the stored identity with the given name
+    fn identity(&self, name: &str) -> Result<&EncryptedIdentity, HolochainError> {
+        self.identities.get(name).ok_or_else(|| {
+            HolochainError::IdentityNotFound(format!("no identity named {} in the keystore", name))
+        })
+    }
+
+    /// the data authenticated alongside an encrypted private key
+    fn aad(name: &str, public_key: &str) -> String {
+        format!("{}\n{}", name, public_key)
+    }
+
+    /// writes every identity to disk, replacing the previous file in one move
+    fn save(&self) -> Result<(), HolochainError> {
+        let json = serde_json::to_string(&KeystoreFile {
+            version: KEYSTORE_VERSION,
+            identities: self.identities.clone(),
+        })?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    extern crate tempfile;
+
+    use self::tempfile::{tempdir, TempDir};
+    use super::Keystore;
+    use agent::keys::{
+        tests::{test_keys, test_keys_b},
+        Key, Keys,
+    };
+    use error::HolochainError;
+    use holochain_agent::Agent;
+    use serde_json;
+    use std::fs;
+
+    /// a low work factor keeps tests fast
+    const TEST_WORK_FACTOR: u8 = 4;
+
+    /// opens an empty keystore in a fresh temporary directory
+    /// the directory is deleted when the returned TempDir is dropped so keep it in scope
+    pub fn test_keystore() -> (Keystore, TempDir) {
+        let dir = tempdir().expect("should be able to create a temporary directory");
+        let mut keystore =
+            Keystore::open(dir.path().join("keystore.json")).expect("should open a new keystore");
+        keystore.set_work_factor(TEST_WORK_FACTOR);
+        (keystore, dir)
+    }
+
+    #[test]
+    /// smoke test
+    fn open() {
+        let (keystore, dir) = test_keystore();
+        assert_eq!(dir.path().join("keystore.json"), keystore.path());
+        assert_eq!(Vec::<String>::new(), keystore.identities());
+    }
+
+    #[test]
+    /// identities round trip through the file
+    fn round_trip() {
+        let (mut keystore, _dir) = test_keystore();
+        keystore
+            .add("alice", &test_keys(), "alice passphrase")
+            .expect("adding an identity shouldn't fail");
+        keystore
+            .add("bob", &test_keys_b(), "bob passphrase")
+            .expect("adding an identity shouldn't fail");
+
+        let reopened = Keystore::open(keystore.path()).expect("should reopen the keystore");
+        assert_eq!(vec!["alice", "bob"], reopened.identities());
+        assert_eq!(Ok(test_keys().public_key()), reopened.public_key("alice"));
+
+        let (agent, keys) = reopened
+            .load("alice", "alice passphrase")
+            .expect("loading with the right passphrase shouldn't fail");
+        assert_eq!(Agent::from_string("alice".to_string()), agent);
+        assert_eq!(test_keys(), keys);
+        assert_eq!(test_keys().node_id(), keys.node_id());
+
+        let (_, keys) = reopened
+            .load("bob", "bob passphrase")
+            .expect("loading with the right passphrase shouldn't fail");
+        assert_eq!(test_keys_b(), keys);
+    }
+
+    #[test]
+    /// the private key never hits the disk unencrypted
+    fn encrypted() {
+        let (mut keystore, _dir) = test_keystore();
+        keystore
+            .add("alice", &test_keys(), "alice passphrase")
+            .expect("adding an identity shouldn't fail");
+
+        let contents = fs::read_to_string(keystore.path()).expect("should read the keystore");
+        assert!(!contents.contains(&test_keys().private_key().to_b58()));
+    }
+
+    #[test]
+    /// the wrong passphrase is a clear error
+    fn wrong_passphrase() {
+        let (mut keystore, _dir) = test_keystore();
+        keystore
+            .add("alice", &test_keys(), "alice passphrase")
+            .expect("adding an identity shouldn't fail");
+
+        assert_eq!(
+            Err(HolochainError::WrongPassphrase(
+                "wrong passphrase for identity alice".to_string()
+            )),
+            keystore.load("alice", "not alice passphrase"),
+        );
+    }
+
+    #[test]
+    /// missing identities are a clear error
+    fn identity_not_found() {
+        let (keystore, _dir) = test_keystore();
+        match keystore.load("carol", "whatever") {
+            Err(HolochainError::IdentityNotFound(_)) => (),
+            other => panic!("expected a missing identity but got {:?}", other),
+        }
+    }
+
+    #[test]
+    /// names are unique and identities can be removed
+    fn add_remove() {
+        let (mut keystore, _dir) = test_keystore();
+        keystore
+            .add("alice", &test_keys(), "alice passphrase")
+            .expect("adding an identity shouldn't fail");
+        assert!(keystore.add("alice", &test_keys_b(), "other").is_err());
+
+        // malformed keys are an error, not a panic
+        let keys = test_keys();
+        for private_key in &[vec![1, 2, 3], test_keys_b().private_key().bytes()] {
+            let malformed = Keys::new(&keys.public_key(), &Key::new(private_key));
+            assert!(keystore.add("bob", &malformed, "bob passphrase").is_err());
+        }
+        assert_eq!(vec!["alice".to_string()], keystore.identities());
+
+        keystore
+            .remove("alice")
+            .expect("removing an identity shouldn't fail");
+        assert_eq!(Vec::<String>::new(), keystore.identities());
+        assert_eq!(
+            Vec::<String>::new(),
+            Keystore::open(keystore.path())
+                .expect("should reopen the keystore")
+                .identities()
+        );
+        match keystore.remove("alice") {
+            Err(HolochainError::IdentityNotFound(_)) => (),
+            other => panic!("expected a missing identity but got {:?}", other),
+        }
+    }
+
+    #[test]
+    /// corrupt files are a clear error
+    fn corrupt_file() {
+        let (keystore, _dir) = test_keystore();
+
+        fs::write(keystore.path(), "not json").expect("should be able to write the keystore");
+        match Keystore::open(keystore.path()) {
+            Err(HolochainError::CorruptKeystore(_)) => (),
+            other => panic!("expected a corrupt keystore but got {:?}", other),
+        }
+
+        fs::write(keystore.path(), r#"{"version":99,"identities":{}}"#)
+            .expect("should be able to write the keystore");
+        match Keystore::open(keystore.path()) {
+            Err(HolochainError::CorruptKeystore(_)) => (),
+            other => panic!("expected a corrupt keystore but got {:?}", other),
+        }
+    }
+
+    #[test]
+    /// corrupt identities are a clear error
+    fn corrupt_identity() {
+        let (mut keystore, _dir) = test_keystore();
+        keystore
+            .add("alice", &test_keys(), "alice passphrase")
+            .expect("adding an identity shouldn't fail");
+
+        let corrupt = |field: &str, value: serde_json::Value| {
+            let mut json: serde_json::Value = serde_json::from_str(
+                &fs::read_to_string(keystore.path()).expect("should read the keystore"),
+            )
+            .expect("the keystore should be json");
+            json["identities"]["alice"][field] = value;
+            let path = keystore.path().with_extension("corrupt");
+            fs::write(&path, json.to_string()).expect("should be able to write the keystore");
+            Keystore::open(&path)
+                .expect("should open the keystore")
+                .load("alice", "alice passphrase")
+        };
+
+        for (field, value) in vec![
+            ("salt", "not b58!".into()),
+            ("nonce", "1".into()),
+            ("tag", "".into()),
+            ("public_key", test_keys_b().public_key().to_b58().into()),
+            ("work_factor", 0.into()),
+        ] {
+            // a swapped public key fails authentication before the key pair check
+            match corrupt(field, value) {
+                Err(HolochainError::CorruptKeystore(_))
+                | Err(HolochainError::WrongPassphrase(_)) => (),
+                other => panic!("expected a corrupt identity but got {:?}", other),
+            }
+        }
+    }
+}
@@ -1,2 +1,5 @@
+pub mod encryption;
 pub mod keys;
+pub mod keystore;
+pub mod rotation;
 pub mod state;
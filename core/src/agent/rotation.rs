@@ -0,0 +1,160 @@
+use agent::keys::{Key, Keys};
+use error::HolochainError;
+use hash::canonical_json;
+use hash_table::entry::Entry;
+use serde_json;
+
+/// entry type of the system entries that record key rotations on the source chain
+/// system entry types start with % so they can't collide with zome entry types
+pub const KEY_ROTATION_ENTRY_TYPE: &str = "%key_rotation";
+
+/// KeyRotation records an agent replacing its keys
+/// old_key = the base58 public key being retired
+/// new_key = the base58 public key replacing it
+/// old_signature = the old key's signature of the rotation, proves the agent authorized it
+/// new_signature = the new key's signature of the rotation, proves the agent holds the new key
+///
+/// headers below the rotation are signed by the old key, headers above it by the new key and the
+/// header of the rotation itself by either
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyRotation {
+    old_key: String,
+    new_key: String,
+    old_signature: String,
+    new_signature: String,
+}
+
+impl KeyRotation {
+    /// builds a new KeyRotation from the old keys to the new keys, signed by both
+    ///
+    /// # Panics
+    ///
+    /// Panics if either keys can't sign, @see Keys::sign()
+    pub fn new(old_keys: &Keys, new_keys: &Keys) -> KeyRotation {
+        let mut rotation = KeyRotation {
+            old_key: old_keys.public_key().to_b58(),
+            new_key: new_keys.public_key().to_b58(),
+            old_signature: String::new(),
+            new_signature: String::new(),
+        };
+        let signed_content = rotation.signed_content();
+        rotation.old_signature = old_keys.sign(signed_content.as_bytes());
+        rotation.new_signature = new_keys.sign(signed_content.as_bytes());
+        rotation
+    }
+
+    /// parses a KeyRotation out of a key rotation entry
+    pub fn from_entry(entry: &Entry) -> Result<KeyRotation, HolochainError> {
+        if entry.entry_type() != KEY_ROTATION_ENTRY_TYPE {
+            return Err(HolochainError::new(&format!(
+                "entry type {} is not a key rotation",
+                entry.entry_type()
+            )));
+        }
+        serde_json::from_str(&entry.content()).map_err(|err| {
+            HolochainError::new(&format!("entry is not a valid key rotation: {}", err))
+        })
+    }
+
+    /// builds the system entry that records the rotation on the chain
+    pub fn to_entry(&self) -> Entry {
+        Entry::new(KEY_ROTATION_ENTRY_TYPE, &canonical_json(&self))
+    }
+
+    /// getter for old_key clone
+    pub fn old_key(&self) -> String {
+        self.old_key.clone()
+    }
+
+    /// getter for new_key clone
+    pub fn new_key(&self) -> String {
+        self.new_key.clone()
+    }
+
+    /// everything covered by the signatures, i.e. the canonical JSON of everything but them
+    fn signed_content(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.old_signature = String::new();
+        unsigned.new_signature = String::new();
+        canonical_json(&unsigned)
+    }
+
+    /// true if the rotation is signed by both the old and new keys
+    pub fn verify(&self) -> bool {
+        let signed_content = self.signed_content();
+        let verify = |key: &str, signature: &str| {
+            Key::from_b58(key)
+                .map(|key| key.verify(signed_content.as_bytes(), signature))
+                .unwrap_or(false)
+        };
+        verify(&self.old_key, &self.old_signature) && verify(&self.new_key, &self.new_signature)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{KeyRotation, KEY_ROTATION_ENTRY_TYPE};
+    use agent::keys::{
+        tests::{test_keys, test_keys_b},
+        Keys, SEED_LENGTH,
+    };
+    use hash_table::entry::Entry;
+
+    /// keys for testing that differ from test_keys() and test_keys_b()
+    pub fn test_keys_c() -> Keys {
+        Keys::from_seed(&[3; SEED_LENGTH])
+    }
+
+    /// dummy rotation from test_keys() to test_keys_b()
+    pub fn test_key_rotation() -> KeyRotation {
+        KeyRotation::new(&test_keys(), &test_keys_b())
+    }
+
+    #[test]
+    /// smoke test KeyRotation::new()
+    fn new() {
+        let rotation = test_key_rotation();
+        assert_eq!(test_keys().public_key().to_b58(), rotation.old_key());
+        assert_eq!(test_keys_b().public_key().to_b58(), rotation.new_key());
+    }
+
+    #[test]
+    /// test rotation.verify()
+    fn verify() {
+        let rotation = test_key_rotation();
+        assert!(rotation.verify());
+
+        // the rotation must be signed by the old key
+        let mut tampered = rotation.clone();
+        tampered.old_key = test_keys_c().public_key().to_b58();
+        assert!(!tampered.verify());
+
+        // the rotation must be signed by the new key
+        let mut tampered = rotation.clone();
+        tampered.new_key = test_keys_c().public_key().to_b58();
+        assert!(!tampered.verify());
+
+        // both signatures are needed
+        let mut tampered = rotation.clone();
+        tampered.new_signature = rotation.old_signature.clone();
+        assert!(!tampered.verify());
+
+        // a garbage key is never valid
+        let mut tampered = rotation.clone();
+        tampered.old_key = "not a key".into();
+        assert!(!tampered.verify());
+    }
+
+    #[test]
+    /// test that rotations round trip through entries
+    fn entry_round_trip() {
+        let rotation = test_key_rotation();
+        let entry = rotation.to_entry();
+        assert_eq!(KEY_ROTATION_ENTRY_TYPE, entry.entry_type());
+        assert_eq!(Ok(rotation), KeyRotation::from_entry(&entry));
+
+        // other entry types aren't rotations
+        assert!(KeyRotation::from_entry(&Entry::new("foo", &entry.content())).is_err());
+        assert!(KeyRotation::from_entry(&Entry::new(KEY_ROTATION_ENTRY_TYPE, "foo")).is_err());
+    }
+}
@@ -1,24 +1,36 @@
 use action::{Action, ActionWrapper, AgentReduceFn};
-use agent::keys::Keys;
+use agent::{
+    encryption::{encrypt_entry, encrypted_by},
+    keys::Keys,
+};
 use chain::Chain;
 use context::Context;
 use error::HolochainError;
+use hash::multihash_algorithm;
 use hash_table::{entry::Entry, memory::MemTable, pair::Pair};
+use holochain_dna::zome::entry_types::Sharing;
 use instance::Observer;
+use serde_json;
 use std::{
     collections::HashMap,
-    rc::Rc,
-    sync::{mpsc::Sender, Arc},
+    sync::{mpsc::Sender, Arc, RwLock},
 };
 
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq)]
 /// struct to track the internal state of an agent exposed to reducers/observers
 pub struct AgentState {
     keys: Option<Keys>,
-    // @TODO how should this work with chains/HTs?
-    // @see https://github.com/holochain/holochain-rust/issues/137
-    // @see https://github.com/holochain/holochain-rust/issues/135
+    /// keys the agent rotated away from, oldest first
+    /// kept so entries encrypted before a rotation can still be decrypted
+    retired_keys: Vec<Keys>,
+    /// the agent's source chain
+    /// the underlying table is shared between every copy of the agent state so it lives as long
+    /// as the instance and is visible to every reducer/observer
+    chain: Chain<MemTable>,
+    /// always the same as the top of the chain, tracked here for cheap access
     top_pair: Option<Pair>,
+    /// the sharing of every entry type in the DNA by entry type name
+    sharing: HashMap<String, Sharing>,
     /// every action and the result of that action
     // @TODO this will blow up memory, implement as some kind of dropping/FIFO with a limit?
     // @see https://github.com/holochain/holochain-rust/issues/166
@@ -30,7 +42,10 @@ impl AgentState {
     pub fn new() -> AgentState {
         AgentState {
             keys: None,
+            retired_keys: Vec::new(),
+            chain: Chain::new(Arc::new(RwLock::new(MemTable::new()))),
             top_pair: None,
+            sharing: HashMap::new(),
             actions: HashMap::new(),
         }
     }
@@ -40,12 +55,53 @@ impl AgentState {
         self.keys.clone()
     }
 
+    /// the current or retired agent keys with the given base58 public key
+    fn keys_for(&self, public_key: &str) -> Option<Keys> {
+        self.keys
+            .iter()
+            .chain(self.retired_keys.iter())
+            .find(|keys| keys.public_key().to_b58() == public_key)
+            .cloned()
+    }
+
+    /// getter for a copy of self.chain
+    /// the copy shares the underlying table with the agent state
+    pub fn chain(&self) -> Chain<MemTable> {
+        self.chain.clone()
+    }
+
     /// getter for a copy of self.top_pair
     /// should be used with a source chain for validation/safety
     pub fn top_pair(&self) -> Option<Pair> {
         self.top_pair.clone()
     }
 
+    /// the sharing of an entry type, entry types that aren't in the DNA are public
+    /// e.g. system entries
+    pub fn sharing(&self, entry_type: &str) -> Sharing {
+        self.sharing.get(entry_type).cloned().unwrap_or_default()
+    }
+
+    /// true if entries of the entry type may leave the local chain, i.e. the entry type is public
+    /// publication and gossip MUST check this for every entry they send to the network
+    /// entries of private and encrypted entry types always stay on the local chain
+    pub fn publishable(&self, entry_type: &str) -> bool {
+        self.sharing(entry_type) == Sharing::Public
+    }
+
+    /// the entry as it is stored for the agent, i.e. encrypted if its entry type is encrypted
+    fn stored_entry(&self, entry: &Entry) -> Result<Entry, HolochainError> {
+        match self.sharing(&entry.entry_type()) {
+            Sharing::Encrypted => match self.keys {
+                Some(ref keys) => encrypt_entry(keys, entry),
+                None => Err(HolochainError::new(
+                    "an agent without keys can't store encrypted entries",
+                )),
+            },
+            _ => Ok(entry.clone()),
+        }
+    }
+
     /// getter for a copy of self.actions
     /// uniquely maps action executions to the result of the action
     pub fn actions(&self) -> HashMap<ActionWrapper, ActionResponse> {
@@ -53,13 +109,43 @@ impl AgentState {
     }
 }
 
+impl Default for AgentState {
+    fn default() -> AgentState {
+        AgentState::new()
+    }
+}
+
+/// a new version of an entry on the source chain
+/// old_hash = the hash of the entry being updated, as returned by commit
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct EntryUpdate {
+    pub old_hash: String,
+    pub new_entry: Entry,
+}
+
+impl EntryUpdate {
+    pub fn new(old_hash: &str, new_entry: &Entry) -> EntryUpdate {
+        EntryUpdate {
+            old_hash: old_hash.into(),
+            new_entry: new_entry.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 /// the agent's response to an action
 /// stored alongside the action in AgentState::actions to provide a state history that observers
 /// poll and retrieve
 pub enum ActionResponse {
     Commit(Result<Pair, HolochainError>),
-    Get(Option<Pair>),
+    Reject(Result<Pair, HolochainError>),
+    Get(Result<Option<Pair>, HolochainError>),
+    UpdateEntry(Result<Pair, HolochainError>),
+    RemoveEntry(Result<Pair, HolochainError>),
+    AddLink(Result<(), HolochainError>),
+    RemoveLink(Result<(), HolochainError>),
+    GetLinks(Result<Vec<String>, HolochainError>),
+    RotateKeys(Result<Pair, HolochainError>),
 }
 
 // @TODO abstract this to a standard trait
@@ -70,13 +156,26 @@ impl ActionResponse {
     // @see https://github.com/holochain/holochain-rust/issues/193
     pub fn to_json(&self) -> String {
         match self {
-            ActionResponse::Commit(result) => match result {
-                Ok(pair) => format!("{{\"hash\":\"{}\"}}", pair.entry().key()),
+            ActionResponse::Commit(result)
+            | ActionResponse::Reject(result)
+            | ActionResponse::UpdateEntry(result)
+            | ActionResponse::RemoveEntry(result)
+            | ActionResponse::RotateKeys(result) => match result {
+                Ok(pair) => format!("{{\"hash\":\"{}\"}}", pair.header().entry()),
                 Err(err) => (*err).to_json(),
             },
             ActionResponse::Get(result) => match result {
-                Some(pair) => pair.to_json(),
-                None => "".to_string(),
+                Ok(Some(pair)) => pair.to_json(),
+                Ok(None) => "".to_string(),
+                Err(err) => (*err).to_json(),
+            },
+            ActionResponse::AddLink(result) | ActionResponse::RemoveLink(result) => match result {
+                Ok(()) => "".to_string(),
+                Err(err) => (*err).to_json(),
+            },
+            ActionResponse::GetLinks(result) => match result {
+                Ok(targets) => serde_json::to_string(targets).expect("links should serialize"),
+                Err(err) => (*err).to_json(),
             },
         }
     }
@@ -89,7 +188,7 @@ impl ActionResponse {
 /// @TODO is there a way to reduce that doesn't block indefinitely on callback fns?
 /// @see https://github.com/holochain/holochain-rust/issues/222
 fn reduce_commit(
-    _context: Arc<Context>,
+    context: Arc<Context>,
     state: &mut AgentState,
     action_wrapper: &ActionWrapper,
     _action_channel: &Sender<ActionWrapper>,
@@ -98,18 +197,103 @@ fn reduce_commit(
     let action = action_wrapper.action();
     let entry = unwrap_to!(action => Action::Commit);
 
-    // add entry to source chain
-    // @TODO this does nothing!
-    // it needs to get something stateless from the agent state that points to
-    // something stateful that can handle an entire hash table (e.g. actor)
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    // @see https://github.com/holochain/holochain-rust/issues/148
-    let mut chain = Chain::new(Rc::new(MemTable::new()));
-
-    state.actions.insert(
-        action_wrapper.clone(),
-        ActionResponse::Commit(chain.push(&entry)),
-    );
+    // add entry to source chain, encrypted if its entry type is encrypted
+    let result = state.stored_entry(entry).and_then(|entry| {
+        state
+            .chain
+            .with_clock(Arc::clone(&context.clock))
+            .push(&entry)
+    });
+    state.top_pair = state.chain.top();
+
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::Commit(result));
+}
+
+/// do a reject action against an agent state
+/// the rejected entry is recorded alongside the source chain without moving its top
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_reject(
+    context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let entry = unwrap_to!(action => Action::Reject);
+
+    let result = state.stored_entry(entry).and_then(|entry| {
+        state
+            .chain
+            .with_clock(Arc::clone(&context.clock))
+            .reject(&entry)
+    });
+
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::Reject(result));
+}
+
+/// hashes everything pushed to the chain with the hash algorithm of the DNA being initialized
+/// a new agent without keys gets the keys from the context, or a freshly generated keypair
+/// the agent keys sign everything pushed to the chain from then on
+/// the sharing of every entry type in the DNA decides how its entries are stored and published
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_init_application(
+    context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let dna = unwrap_to!(action => Action::InitApplication);
+
+    state.chain = state
+        .chain
+        .with_hash_algorithm(multihash_algorithm(dna.hash_algorithm));
+
+    // entries carry their type but not their zome, so an entry type that several zomes share
+    // differently gets the most restrictive sharing whatever the order of the zomes
+    for entry_type in dna.zomes.iter().flat_map(|zome| zome.entry_types.iter()) {
+        if sharing_precedence(&entry_type.sharing)
+            > sharing_precedence(&state.sharing(&entry_type.name))
+        {
+            state
+                .sharing
+                .insert(entry_type.name.clone(), entry_type.sharing.clone());
+        }
+    }
+
+    if state.keys.is_none() {
+        match context.keys.clone().map_or_else(Keys::generate, Ok) {
+            Ok(keys) => state.keys = Some(keys),
+            // @TODO surface errors from reducers
+            // @see https://github.com/holochain/holochain-rust/issues/97
+            Err(err) => {
+                let _ = context.log(&format!("could not generate agent keys: {}", err));
+            }
+        }
+    }
+
+    if let Some(keys) = state.keys() {
+        state.chain = state.chain.with_keys(keys);
+    }
+}
+
+/// the precedence of a sharing when zomes share an entry type differently, highest wins
+/// Encrypted > Private > Public
+/// only entries of public entry types may ever be published, so encrypted entries are as private
+/// as private entries and are encrypted at rest on top of that
+/// @see AgentState::publishable()
+fn sharing_precedence(sharing: &Sharing) -> u8 {
+    match sharing {
+        Sharing::Public => 0,
+        Sharing::Private => 1,
+        Sharing::Encrypted => 2,
+    }
 }
 
 /// do a get action against an agent state
@@ -125,33 +309,206 @@ fn reduce_get(
     let key = unwrap_to!(action => Action::Get);
 
     // get pair from source chain
-    // @TODO this does nothing!
-    // it needs to get something stateless from the agent state that points to
-    // something stateful that can handle an entire hash table (e.g. actor)
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    // @see https://github.com/holochain/holochain-rust/issues/148
+    // @TODO if the get fails local, do a network get
+    // @see https://github.com/holochain/holochain-rust/issues/167
+    let result = match state.chain.get_entry(key) {
+        // the agent's own encrypted entries are decrypted by the keys that encrypted them, which
+        // are retired if the agent rotated its keys since, anything else is returned as stored
+        // an entry the agent keys can't decrypt is an error rather than ciphertext passed off as
+        // the content
+        Ok(Some(ref pair)) if state.sharing(&pair.header().entry_type()) == Sharing::Encrypted => {
+            encrypted_by(pair.entry()).and_then(|key| match state.keys_for(&key) {
+                Some(keys) => pair.decrypt(&keys).map(Some),
+                None => Err(HolochainError::new(&format!(
+                    "the agent has no keys {} to decrypt the entry with",
+                    key
+                ))),
+            })
+        }
+        result => result,
+    };
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::Get(result));
+}
 
-    // drop in a dummy entry for testing
-    let mut chain = Chain::new(Rc::new(MemTable::new()));
-    let e = Entry::new("testEntryType", "test entry content");
-    chain.push(&e).expect("test entry should be valid");
+/// the pair of an entry on the source chain as it is stored
+fn chain_pair(state: &AgentState, entry_hash: &str) -> Result<Pair, HolochainError> {
+    match state.chain.get_entry(entry_hash)? {
+        Some(pair) => Ok(pair),
+        None => Err(HolochainError::new(&format!(
+            "entry {} is not on the source chain",
+            entry_hash
+        ))),
+    }
+}
 
-    // @TODO if the get fails local, do a network get
+/// do an update entry action against an agent state
+/// the new version is stored as per commit and the old version is marked MODIFIED
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_update_entry(
+    context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let update = unwrap_to!(action => Action::UpdateEntry);
+
+    let result = chain_pair(state, &update.old_hash).and_then(|old_pair| {
+        state.stored_entry(&update.new_entry).and_then(|entry| {
+            state
+                .chain
+                .with_clock(Arc::clone(&context.clock))
+                .update(&old_pair, &entry)
+        })
+    });
+    state.top_pair = state.chain.top();
+
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::UpdateEntry(result));
+}
+
+/// do a remove entry action against an agent state
+/// a deletion record is pushed and the entry is marked DELETED
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_remove_entry(
+    context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let entry_hash = unwrap_to!(action => Action::RemoveEntry);
+
+    let result = chain_pair(state, entry_hash).and_then(|pair| {
+        state
+            .chain
+            .with_clock(Arc::clone(&context.clock))
+            .remove(&pair)
+    });
+    state.top_pair = state.chain.top();
+
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::RemoveEntry(result));
+}
+
+/// do an add link action against an agent state
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_add_link(
+    _context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let link = unwrap_to!(action => Action::AddLink);
+
+    let result = state.chain.add_link(link);
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::AddLink(result));
+}
+
+/// do a remove link action against an agent state
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_remove_link(
+    _context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let link = unwrap_to!(action => Action::RemoveLink);
+
+    let result = state.chain.remove_link(link);
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::RemoveLink(result));
+}
+
+/// do a get links action against an agent state
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_get_links(
+    _context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let query = unwrap_to!(action => Action::GetLinks);
+
+    // @TODO if the base isn't local, do a network get
     // @see https://github.com/holochain/holochain-rust/issues/167
+    let result = state.chain.get_links(query);
+    state
+        .actions
+        .insert(action_wrapper.clone(), ActionResponse::GetLinks(result));
+}
+
+/// do a rotate keys action against an agent state
+/// the new keys are looked up in the context by their public key
+/// the rotation from the current agent keys is pushed to the source chain, then the agent keys
+/// and the keys owning the chain are replaced together so everything after it is signed by the
+/// new keys
+/// intended for use inside the reducer, isolated for unit testing
+fn reduce_rotate_keys(
+    context: Arc<Context>,
+    state: &mut AgentState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let new_key = unwrap_to!(action => Action::RotateKeys);
+
+    let result = match (state.keys(), context.get_rotation_keys(new_key)) {
+        (Some(old_keys), Ok(new_keys)) => {
+            let result = state
+                .chain
+                .with_clock(Arc::clone(&context.clock))
+                .rotate_keys(&old_keys, &new_keys);
+            if result.is_ok() {
+                state.retired_keys.push(old_keys);
+                state.keys = Some(new_keys.clone());
+                state.chain = state.chain.with_keys(new_keys);
+                // the agent state holds the keys now
+                let _ = context.remove_rotation_keys(new_key);
+            }
+            result
+        }
+        (None, _) => Err(HolochainError::new(
+            "an agent without keys can't rotate its keys",
+        )),
+        (_, Err(err)) => Err(err),
+    };
+    state.top_pair = state.chain.top();
 
-    let result = chain
-        .get_entry(&key)
-        .expect("should be able to get entry that we just added");
     state
         .actions
-        .insert(action_wrapper.clone(), ActionResponse::Get(result.clone()));
+        .insert(action_wrapper.clone(), ActionResponse::RotateKeys(result));
 }
 
 /// maps incoming action to the correct handler
 fn resolve_reducer(action_wrapper: &ActionWrapper) -> Option<AgentReduceFn> {
     match action_wrapper.action() {
         Action::Commit(_) => Some(reduce_commit),
+        Action::Reject(_) => Some(reduce_reject),
         Action::Get(_) => Some(reduce_get),
+        Action::UpdateEntry(_) => Some(reduce_update_entry),
+        Action::RemoveEntry(_) => Some(reduce_remove_entry),
+        Action::AddLink(_) => Some(reduce_add_link),
+        Action::RemoveLink(_) => Some(reduce_remove_link),
+        Action::GetLinks(_) => Some(reduce_get_links),
+        Action::RotateKeys(_) => Some(reduce_rotate_keys),
+        Action::InitApplication(_) => Some(reduce_init_application),
         _ => None,
     }
 }
@@ -183,12 +540,42 @@ pub fn reduce(
 
 #[cfg(test)]
 pub mod tests {
-    use super::{reduce_commit, reduce_get, ActionResponse, AgentState};
-    use action::tests::{test_action_wrapper_commit, test_action_wrapper_get};
+    use super::{
+        reduce_commit, reduce_get, reduce_init_application, reduce_reject, resolve_reducer,
+        ActionResponse, AgentState, EntryUpdate,
+    };
+    use action::{
+        tests::{test_action_wrapper_commit, test_action_wrapper_get},
+        Action, ActionWrapper,
+    };
+    use agent::{
+        keys::tests::{test_keys, test_keys_b},
+        rotation::KEY_ROTATION_ENTRY_TYPE,
+    };
+    use chain::DELETION_ENTRY_TYPE;
+    use context::Context;
     use error::HolochainError;
-    use hash_table::pair::tests::test_pair;
+    use hash::{algorithm_of, DEFAULT_HASH};
+    use hash_table::{
+        entry::{
+            tests::{test_content_b, test_entry, test_entry_a, test_type},
+            Entry,
+        },
+        links::tests::{test_link, test_link_query},
+        pair::{tests::test_pair, Pair},
+        status::CRUDStatus,
+        HashTable,
+    };
+    use holochain_dna::{
+        zome::{
+            entry_types::{EntryType, Sharing},
+            Config, Zome,
+        },
+        Dna, HashAlgorithm,
+    };
     use instance::tests::{test_context, test_instance_blank};
-    use std::collections::HashMap;
+    use multihash::Hash;
+    use std::{collections::HashMap, sync::Arc};
 
     /// dummy agent state
     pub fn test_agent_state() -> AgentState {
@@ -202,7 +589,75 @@ pub mod tests {
 
     /// dummy action response for a successful get as test_pair()
     pub fn test_action_response_get() -> ActionResponse {
-        ActionResponse::Get(Some(test_pair()))
+        ActionResponse::Get(Ok(Some(test_pair())))
+    }
+
+    /// dna with an entry type for each sharing, named after the sharing
+    /// the "shared" entry type is public in one zome and private in another
+    /// the "hidden" entry type is encrypted in one zome and private in another
+    pub fn test_sharing_dna() -> Dna {
+        let entry_type = |name: &str, sharing: Sharing| {
+            let mut entry_type = EntryType::new();
+            entry_type.name = name.into();
+            entry_type.sharing = sharing;
+            entry_type
+        };
+        let mut dna = Dna::new();
+        dna.zomes.push(Zome::new(
+            "zome_a",
+            "",
+            &Config::new(),
+            &[
+                entry_type("public", Sharing::Public),
+                entry_type("private", Sharing::Private),
+                entry_type("encrypted", Sharing::Encrypted),
+                entry_type("shared", Sharing::Public),
+                entry_type("hidden", Sharing::Encrypted),
+            ],
+            &[],
+        ));
+        dna.zomes.push(Zome::new(
+            "zome_b",
+            "",
+            &Config::new(),
+            &[
+                entry_type("shared", Sharing::Private),
+                entry_type("hidden", Sharing::Private),
+            ],
+            &[],
+        ));
+        dna
+    }
+
+    /// agent state initialized with test_sharing_dna()
+    fn test_sharing_state() -> AgentState {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+        reduce_init_application(
+            test_context("bob"),
+            &mut state,
+            &ActionWrapper::new(Action::InitApplication(test_sharing_dna())),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        state
+    }
+
+    /// commits an entry to the agent state, returning the response
+    fn test_commit(state: &mut AgentState, entry: Entry) -> Result<Pair, HolochainError> {
+        let instance = test_instance_blank();
+        let action_wrapper = ActionWrapper::new(Action::Commit(entry));
+        reduce_commit(
+            test_context("bob"),
+            state,
+            &action_wrapper,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        match state.actions().get(&action_wrapper) {
+            Some(ActionResponse::Commit(result)) => result.clone(),
+            response => panic!("unexpected response to commit: {:?}", response),
+        }
     }
 
     #[test]
@@ -217,6 +672,12 @@ pub mod tests {
         assert_eq!(None, test_agent_state().keys());
     }
 
+    #[test]
+    /// test for the agent state chain getter
+    fn agent_state_chain() {
+        assert_eq!(None, test_agent_state().chain().top());
+    }
+
     #[test]
     /// test for the agent state top pair getter
     fn agent_state_top_pair() {
@@ -249,16 +710,281 @@ pub mod tests {
             state.actions().get(&action_wrapper),
             Some(&test_action_response_commit()),
         );
+        // the commit is kept in the agent's source chain
+        assert_eq!(Some(test_pair()), state.top_pair());
+        assert_eq!(Some(test_pair()), state.chain().top());
+    }
+
+    #[test]
+    /// test that commits accumulate on the same source chain across copies of the state
+    fn test_reduce_commit_persists() {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+
+        let first = test_action_wrapper_commit();
+        reduce_commit(
+            test_context("bob"),
+            &mut state,
+            &first,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+
+        // reducers work on a copy of the previous state
+        let mut state = state.clone();
+        let second = test_action_wrapper_commit();
+        reduce_commit(
+            test_context("bob"),
+            &mut state,
+            &second,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+
+        assert_eq!(2, state.chain().iter().count());
+        assert_eq!(state.chain().top(), state.top_pair());
+        // the first commit is at the bottom of the chain
+        assert_eq!(Some(test_pair()), state.chain().iter().last());
+    }
+
+    #[test]
+    /// test for reducing reject
+    fn test_reduce_reject() {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+
+        // only an agent with keys can reject entries
+        let unowned = ActionWrapper::new(Action::Reject(test_entry()));
+        reduce_reject(
+            test_context("bob"),
+            &mut state,
+            &unowned,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        match state.actions().get(&unowned) {
+            Some(ActionResponse::Reject(Err(_))) => (),
+            response => panic!("unexpected response to reject: {:?}", response),
+        }
+
+        state.chain = state.chain.with_keys(test_keys());
+        let action_wrapper = ActionWrapper::new(Action::Reject(test_entry()));
+        reduce_reject(
+            test_context("bob"),
+            &mut state,
+            &action_wrapper,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+
+        let pair = match state.actions().get(&action_wrapper) {
+            Some(ActionResponse::Reject(Ok(pair))) => pair.clone(),
+            response => panic!("unexpected response to reject: {:?}", response),
+        };
+        assert_eq!(&test_entry(), pair.entry());
+        // rejected entries are not part of the source chain
+        assert_eq!(None, state.top_pair());
+        assert_eq!(None, state.chain().top());
+        assert_eq!(
+            Ok(CRUDStatus::REJECTED),
+            state.chain().table().read().unwrap().get_status(&pair),
+        );
+    }
+
+    #[test]
+    /// test that initializing an application hashes the chain with the DNA hash algorithm
+    fn test_reduce_init_application() {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+        assert_eq!(DEFAULT_HASH, state.chain().hash_algorithm());
+
+        let mut dna = Dna::new();
+        dna.hash_algorithm = HashAlgorithm::Blake2b512;
+        reduce_init_application(
+            test_context("bob"),
+            &mut state,
+            &ActionWrapper::new(Action::InitApplication(dna)),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        assert_eq!(Hash::Blake2b, state.chain().hash_algorithm());
+        // the new agent has keys
+        assert!(state.keys().is_some());
+
+        reduce_commit(
+            test_context("bob"),
+            &mut state,
+            &test_action_wrapper_commit(),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        let top = state.top_pair().expect("the commit should be on the chain");
+        assert_eq!(Some(Hash::Blake2b), algorithm_of(&top.key()));
+        assert_eq!(Some(Hash::Blake2b), algorithm_of(&top.header().entry()));
+        // the agent keys sign the commit
+        assert_eq!(state.keys(), state.chain().keys());
+        assert_eq!(
+            state.keys().map(|keys| keys.public_key().to_b58()),
+            Some(top.header().source())
+        );
+        assert!(top.header().verify_signature());
+        assert_eq!(Ok(()), state.chain().verify());
+    }
+
+    #[test]
+    /// test that initializing an application uses the agent keys from the context
+    fn test_reduce_init_application_context_keys() {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+
+        let mut context = (*test_context("bob")).clone();
+        context.keys = Some(test_keys());
+        reduce_init_application(
+            Arc::new(context),
+            &mut state,
+            &ActionWrapper::new(Action::InitApplication(Dna::new())),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        assert_eq!(Some(test_keys()), state.keys());
+    }
+
+    #[test]
+    /// test that initializing an application records the sharing of every entry type
+    fn test_reduce_init_application_sharing() {
+        let state = test_sharing_state();
+        assert_eq!(Sharing::Public, state.sharing("public"));
+        assert_eq!(Sharing::Private, state.sharing("private"));
+        assert_eq!(Sharing::Encrypted, state.sharing("encrypted"));
+        // the most restrictive sharing of an entry type wins
+        assert_eq!(Sharing::Private, state.sharing("shared"));
+        assert_eq!(Sharing::Encrypted, state.sharing("hidden"));
+        // entry types outside the DNA are public
+        assert_eq!(Sharing::Public, state.sharing("xxx"));
+
+        // whatever the order of the zomes
+        let mut dna = test_sharing_dna();
+        dna.zomes.reverse();
+        let mut reversed = test_agent_state();
+        let instance = test_instance_blank();
+        reduce_init_application(
+            test_context("bob"),
+            &mut reversed,
+            &ActionWrapper::new(Action::InitApplication(dna)),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        assert_eq!(state.sharing, reversed.sharing);
+    }
+
+    #[test]
+    /// test that only entries of public entry types are publishable
+    fn test_publishable() {
+        let state = test_sharing_state();
+        assert!(state.publishable("public"));
+        assert!(!state.publishable("private"));
+        assert!(!state.publishable("encrypted"));
+        assert!(!state.publishable("shared"));
+        assert!(!state.publishable("hidden"));
+        // e.g. system entries
+        assert!(state.publishable("xxx"));
+        assert!(state.publishable(KEY_ROTATION_ENTRY_TYPE));
+    }
+
+    #[test]
+    /// test that entries of encrypted entry types are encrypted at rest and decrypted on get
+    fn test_reduce_commit_encrypted() {
+        let mut state = test_sharing_state();
+        let instance = test_instance_blank();
+        let entry = Entry::new("encrypted", "secret content");
+
+        let pair = test_commit(&mut state, entry.clone()).expect("commit shouldn't fail");
+        assert_eq!(Some(pair.clone()), state.top_pair());
+        assert!(!pair.entry().content().contains("secret content"));
+        assert_eq!(Ok(()), state.chain().verify());
+
+        // the owner gets the decrypted entry
+        let action_wrapper = ActionWrapper::new(Action::Get(pair.header().entry()));
+        reduce_get(
+            test_context("bob"),
+            &mut state,
+            &action_wrapper,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        match state.actions().get(&action_wrapper) {
+            Some(ActionResponse::Get(Ok(Some(got)))) => {
+                assert_eq!(pair.header(), got.header());
+                assert_eq!(&entry, got.entry());
+            }
+            response => panic!("unexpected response to get: {:?}", response),
+        }
+
+        // entries encrypted before the agent rotates its keys are still readable after
+        let context = test_context("bob");
+        let new_key = context
+            .add_rotation_keys(test_keys_b())
+            .expect("adding rotation keys shouldn't fail");
+        let mut rotated = state.clone();
+        match test_reduce_in_context(context, &mut rotated, Action::RotateKeys(new_key)) {
+            Some(ActionResponse::RotateKeys(Ok(_))) => (),
+            response => panic!("unexpected response to rotate keys: {:?}", response),
+        }
+        assert_eq!(Some(test_keys_b()), rotated.keys());
+        match test_reduce(&mut rotated, Action::Get(pair.header().entry())) {
+            Some(ActionResponse::Get(Ok(Some(got)))) => assert_eq!(&entry, got.entry()),
+            response => panic!("unexpected response to get: {:?}", response),
+        }
+
+        // the ciphertext is never returned as the entry, e.g. to an agent without the keys
+        let mut stranger = state.clone();
+        stranger.keys = Some(test_keys_b());
+        match test_reduce(&mut stranger, Action::Get(pair.header().entry())) {
+            Some(ActionResponse::Get(Err(_))) => (),
+            response => panic!("unexpected response to get: {:?}", response),
+        }
+
+        // other entries are stored as they are
+        let pair = test_commit(&mut state, Entry::new("private", "private content"))
+            .expect("commit shouldn't fail");
+        assert_eq!("private content", pair.entry().content());
+
+        // encrypted entries need keys
+        let mut state = test_agent_state();
+        state.sharing = test_sharing_state().sharing;
+        assert!(test_commit(&mut state, entry).is_err());
+        assert_eq!(None, state.top_pair());
     }
 
     #[test]
     /// test for reducing get
     fn test_reduce_get() {
         let mut state = test_agent_state();
+        let instance = test_instance_blank();
+
+        // nothing to get before anything is committed
         let action_wrapper = test_action_wrapper_get();
+        reduce_get(
+            test_context("foo"),
+            &mut state,
+            &action_wrapper,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        assert_eq!(
+            state.actions().get(&action_wrapper),
+            Some(&ActionResponse::Get(Ok(None))),
+        );
 
-        let instance = test_instance_blank();
+        reduce_commit(
+            test_context("foo"),
+            &mut state,
+            &test_action_wrapper_commit(),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
 
+        let action_wrapper = test_action_wrapper_get();
         reduce_get(
             test_context("foo"),
             &mut state,
@@ -277,18 +1003,250 @@ pub mod tests {
     /// test response to json
     fn test_response_to_json() {
         assert_eq!(
-            "{\"hash\":\"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT\"}",
+            "{\"hash\":\"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj\"}",
             ActionResponse::Commit(Ok(test_pair())).to_json(),
         );
         assert_eq!(
             "{\"error\":\"some error\"}",
             ActionResponse::Commit(Err(HolochainError::new("some error"))).to_json(),
         );
+        assert_eq!(
+            "{\"hash\":\"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj\"}",
+            ActionResponse::Reject(Ok(test_pair())).to_json(),
+        );
+        assert_eq!(
+            "{\"hash\":\"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj\"}",
+            ActionResponse::UpdateEntry(Ok(test_pair())).to_json(),
+        );
+        assert_eq!(
+            "{\"error\":\"some error\"}",
+            ActionResponse::RemoveEntry(Err(HolochainError::new("some error"))).to_json(),
+        );
+
+        assert_eq!(
+            "{\"header\":{\"entry_type\":\"testEntryType\",\"time\":\"2018-08-01T00:00:00+00:00\",\"next\":null,\"entry\":\"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj\",\"type_next\":null,\"source\":\"\",\"signature\":\"\"},\"entry\":{\"content\":\"test entry content\",\"entry_type\":\"testEntryType\"}}",
+            ActionResponse::Get(Ok(Some(test_pair()))).to_json(),
+        );
+        assert_eq!("", ActionResponse::Get(Ok(None)).to_json());
+        assert_eq!(
+            "{\"error\":\"some error\"}",
+            ActionResponse::Get(Err(HolochainError::new("some error"))).to_json(),
+        );
+
+        assert_eq!("", ActionResponse::AddLink(Ok(())).to_json());
+        assert_eq!(
+            "{\"error\":\"some error\"}",
+            ActionResponse::RemoveLink(Err(HolochainError::new("some error"))).to_json(),
+        );
+        assert_eq!(
+            "[\"a\",\"b\"]",
+            ActionResponse::GetLinks(Ok(vec!["a".into(), "b".into()])).to_json(),
+        );
+    }
+
+    /// agent state initialized with a blank DNA, so the agent has keys
+    fn test_owned_state() -> AgentState {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+        reduce_init_application(
+            test_context("bob"),
+            &mut state,
+            &ActionWrapper::new(Action::InitApplication(Dna::new())),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        state
+    }
+
+    /// reduces any action against the agent state, returning the response
+    fn test_reduce(state: &mut AgentState, action: Action) -> Option<ActionResponse> {
+        test_reduce_in_context(test_context("bob"), state, action)
+    }
 
+    /// reduces any action against the agent state in the given context, returning the response
+    fn test_reduce_in_context(
+        context: Arc<Context>,
+        state: &mut AgentState,
+        action: Action,
+    ) -> Option<ActionResponse> {
+        let instance = test_instance_blank();
+        let action_wrapper = ActionWrapper::new(action);
+        let reduce = resolve_reducer(&action_wrapper).expect("the action should reduce");
+        reduce(
+            context,
+            state,
+            &action_wrapper,
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+        state.actions().get(&action_wrapper).cloned()
+    }
+
+    #[test]
+    /// test for reducing update entry
+    fn test_reduce_update_entry() {
+        let mut state = test_owned_state();
+        let new_entry = Entry::new(&test_type(), &test_content_b());
+        let update = EntryUpdate::new(&test_entry().hash(), &new_entry);
+
+        // the old entry must be committed first
+        match test_reduce(&mut state, Action::UpdateEntry(update.clone())) {
+            Some(ActionResponse::UpdateEntry(Err(_))) => (),
+            response => panic!("unexpected response to update entry: {:?}", response),
+        }
+
+        let old_pair = test_commit(&mut state, test_entry()).expect("commit shouldn't fail");
+        let new_pair = match test_reduce(&mut state, Action::UpdateEntry(update.clone())) {
+            Some(ActionResponse::UpdateEntry(Ok(pair))) => pair,
+            response => panic!("unexpected response to update entry: {:?}", response),
+        };
+        assert_eq!(&new_entry, new_pair.entry());
+        assert_eq!(Some(new_pair.clone()), state.top_pair());
         assert_eq!(
-            "{\"header\":{\"entry_type\":\"testEntryType\",\"time\":\"\",\"next\":null,\"entry\":\"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT\",\"type_next\":null,\"signature\":\"\"},\"entry\":{\"content\":\"test entry content\",\"entry_type\":\"testEntryType\"}}",
-            ActionResponse::Get(Some(test_pair())).to_json(),
+            Ok(CRUDStatus::MODIFIED),
+            state.chain().table().read().unwrap().get_status(&old_pair),
         );
-        assert_eq!("", ActionResponse::Get(None).to_json());
+
+        // the old version can't be updated again
+        match test_reduce(&mut state, Action::UpdateEntry(update)) {
+            Some(ActionResponse::UpdateEntry(Err(_))) => (),
+            response => panic!("unexpected response to update entry: {:?}", response),
+        }
+        assert_eq!(Some(new_pair), state.top_pair());
+    }
+
+    #[test]
+    /// test for reducing remove entry
+    fn test_reduce_remove_entry() {
+        let mut state = test_owned_state();
+
+        // the entry must be committed first
+        match test_reduce(&mut state, Action::RemoveEntry(test_entry().hash())) {
+            Some(ActionResponse::RemoveEntry(Err(_))) => (),
+            response => panic!("unexpected response to remove entry: {:?}", response),
+        }
+
+        let pair = test_commit(&mut state, test_entry()).expect("commit shouldn't fail");
+        let deletion = match test_reduce(&mut state, Action::RemoveEntry(test_entry().hash())) {
+            Some(ActionResponse::RemoveEntry(Ok(deletion))) => deletion,
+            response => panic!("unexpected response to remove entry: {:?}", response),
+        };
+        assert_eq!(DELETION_ENTRY_TYPE, deletion.entry().entry_type());
+        assert_eq!(Some(deletion.clone()), state.top_pair());
+        assert_eq!(
+            Ok(CRUDStatus::DELETED),
+            state.chain().table().read().unwrap().get_status(&pair),
+        );
+
+        // removed entries can't be removed again
+        match test_reduce(&mut state, Action::RemoveEntry(test_entry().hash())) {
+            Some(ActionResponse::RemoveEntry(Err(_))) => (),
+            response => panic!("unexpected response to remove entry: {:?}", response),
+        }
+        assert_eq!(Some(deletion), state.top_pair());
+    }
+
+    #[test]
+    /// test for reducing add link, remove link and get links
+    fn test_reduce_links() {
+        let mut state = test_agent_state();
+        let instance = test_instance_blank();
+        reduce_init_application(
+            test_context("bob"),
+            &mut state,
+            &ActionWrapper::new(Action::InitApplication(Dna::new())),
+            &instance.action_channel().clone(),
+            &instance.observer_channel().clone(),
+        );
+
+        let mut reduce_link = |action: Action| {
+            let action_wrapper = ActionWrapper::new(action);
+            let reduce = resolve_reducer(&action_wrapper).expect("link actions should reduce");
+            reduce(
+                test_context("bob"),
+                &mut state,
+                &action_wrapper,
+                &instance.action_channel().clone(),
+                &instance.observer_channel().clone(),
+            );
+            state.actions().get(&action_wrapper).cloned()
+        };
+
+        // the base must be committed first
+        match reduce_link(Action::AddLink(test_link())) {
+            Some(ActionResponse::AddLink(Err(_))) => (),
+            response => panic!("unexpected response to add link: {:?}", response),
+        }
+
+        reduce_link(Action::Commit(test_entry_a()));
+        assert_eq!(
+            Some(ActionResponse::AddLink(Ok(()))),
+            reduce_link(Action::AddLink(test_link()))
+        );
+        assert_eq!(
+            Some(ActionResponse::GetLinks(Ok(vec![test_link().target]))),
+            reduce_link(Action::GetLinks(test_link_query()))
+        );
+
+        assert_eq!(
+            Some(ActionResponse::RemoveLink(Ok(()))),
+            reduce_link(Action::RemoveLink(test_link()))
+        );
+        assert_eq!(
+            Some(ActionResponse::GetLinks(Ok(vec![]))),
+            reduce_link(Action::GetLinks(test_link_query()))
+        );
+    }
+
+    #[test]
+    /// test for reducing rotate keys
+    fn test_reduce_rotate_keys() {
+        let context = test_context("bob");
+        let new_key = test_keys_b().public_key().to_b58();
+        let mut state = test_owned_state();
+
+        // the new keys must be added to the context first
+        match test_reduce(&mut state, Action::RotateKeys(new_key.clone())) {
+            Some(ActionResponse::RotateKeys(Err(_))) => (),
+            response => panic!("unexpected response to rotate keys: {:?}", response),
+        }
+        assert_eq!(
+            Ok(new_key.clone()),
+            context.add_rotation_keys(test_keys_b())
+        );
+
+        // only an agent with keys can rotate them
+        match test_reduce_in_context(
+            Arc::clone(&context),
+            &mut test_agent_state(),
+            Action::RotateKeys(new_key.clone()),
+        ) {
+            Some(ActionResponse::RotateKeys(Err(_))) => (),
+            response => panic!("unexpected response to rotate keys: {:?}", response),
+        }
+
+        let old_keys = state.keys().expect("the agent should have keys");
+        test_commit(&mut state, test_entry_a()).expect("commit shouldn't fail");
+        let rotation = match test_reduce_in_context(
+            Arc::clone(&context),
+            &mut state,
+            Action::RotateKeys(new_key.clone()),
+        ) {
+            Some(ActionResponse::RotateKeys(Ok(rotation))) => rotation,
+            response => panic!("unexpected response to rotate keys: {:?}", response),
+        };
+        assert_eq!(KEY_ROTATION_ENTRY_TYPE, rotation.entry().entry_type());
+        assert_eq!(old_keys.public_key().to_b58(), rotation.header().source());
+        assert_eq!(Some(rotation), state.top_pair());
+
+        // the agent and its chain both sign with the new keys from now on
+        assert_eq!(Some(test_keys_b()), state.keys());
+        assert_eq!(Some(test_keys_b()), state.chain().keys());
+        let pair = test_commit(&mut state, test_entry()).expect("commit shouldn't fail");
+        assert_eq!(test_keys_b().public_key().to_b58(), pair.header().source());
+        assert_eq!(Ok(()), state.chain().verify());
+
+        // the context doesn't keep keys the agent has rotated to
+        assert!(context.get_rotation_keys(&new_key).is_err());
     }
 }
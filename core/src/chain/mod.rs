@@ -1,28 +1,45 @@
 // pub mod memory;
+use agent::{
+    keys::Keys,
+    rotation::{KeyRotation, KEY_ROTATION_ENTRY_TYPE},
+};
+use chrono::{DateTime, Utc};
+use clock::{Clock, SystemClock};
 use error::HolochainError;
-use hash_table::{entry::Entry, pair::Pair, HashTable};
+use hash::DEFAULT_HASH;
+use hash_table::{
+    entry::Entry,
+    links::{Link, LinkQuery},
+    pair::Pair,
+    pair_meta::PairMeta,
+    status::{CRUDStatus, LINK_NAME, STATUS_NAME},
+    HashTable, HashTableOp,
+};
+use multihash::Hash;
 use serde_json;
-use std::{fmt, rc::Rc};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// entry type of the system entries that record the removal of a pair from the source chain
+/// the content of the entry is the key of the removed pair
+pub const DELETION_ENTRY_TYPE: &str = "%deletion";
 
 /// Iterator type for pairs in a chain
 /// next method may panic if there is an error in the underlying table
 #[derive(Clone)]
 pub struct ChainIterator<T: HashTable> {
-    // @TODO thread safe table references
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    table: Rc<T>,
+    table: Arc<RwLock<T>>,
     current: Option<Pair>,
 }
 
 impl<T: HashTable> ChainIterator<T> {
-    // @TODO table implementation is changing anyway so waste of time to mess with ref/value
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    #[allow(unknown_lints)]
-    #[allow(needless_pass_by_value)]
-    pub fn new(table: Rc<T>, pair: Option<Pair>) -> ChainIterator<T> {
+    pub fn new(table: Arc<RwLock<T>>, pair: Option<Pair>) -> ChainIterator<T> {
         ChainIterator {
             current: pair,
-            table: Rc::clone(&table),
+            table,
         }
     }
 }
@@ -33,28 +50,95 @@ impl<T: HashTable> Iterator for ChainIterator<T> {
     /// May panic if there is an underlying error in the table
     fn next(&mut self) -> Option<Pair> {
         let previous = self.current.take();
-        self.current = previous.as_ref()
-                        .and_then(|p| p.header().next())
-                        // @TODO should this panic?
-                        // @see https://github.com/holochain/holochain-rust/issues/146
-                        .and_then(|h| self.table.get(&h).expect("getting from a table shouldn't fail"));
+        self.current = previous
+            .as_ref()
+            .and_then(|p| p.header().next())
+            // @TODO should this panic?
+            // @see https://github.com/holochain/holochain-rust/issues/146
+            .and_then(|h| {
+                self.table
+                    .read()
+                    .expect("owners of the table RwLock shouldn't panic")
+                    .get(&h)
+                    .expect("getting from a table shouldn't fail")
+            });
         previous
     }
 }
 
+/// Iterator type for pairs of a single entry type in a chain, follows type_next rather than next
+/// next method may panic if there is an error in the underlying table
+#[derive(Clone)]
+pub struct ChainTypeIterator<T: HashTable> {
+    table: Arc<RwLock<T>>,
+    current: Option<Pair>,
+}
+
+impl<T: HashTable> ChainTypeIterator<T> {
+    pub fn new(table: Arc<RwLock<T>>, pair: Option<Pair>) -> ChainTypeIterator<T> {
+        ChainTypeIterator {
+            current: pair,
+            table,
+        }
+    }
+}
+
+impl<T: HashTable> Iterator for ChainTypeIterator<T> {
+    type Item = Pair;
+
+    /// May panic if there is an underlying error in the table
+    fn next(&mut self) -> Option<Pair> {
+        let previous = self.current.take();
+        self.current = previous
+            .as_ref()
+            .and_then(|p| p.header().type_next())
+            // @TODO should this panic?
+            // @see https://github.com/holochain/holochain-rust/issues/146
+            .and_then(|h| {
+                self.table
+                    .read()
+                    .expect("owners of the table RwLock shouldn't panic")
+                    .get(&h)
+                    .expect("getting from a table shouldn't fail")
+            });
+        previous
+    }
+}
+
+/// a source chain of Pairs backed by a HashTable
+/// the table and top are shared (not copied) between clones of the chain so a chain can be handed
+/// across threads, e.g. held in the agent state, read by observers and zome function calls
+/// reads are concurrent, pushes from any clone are serialized against the same top
 pub struct Chain<T: HashTable> {
-    // @TODO thread safe table references
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    table: Rc<T>,
-    top: Option<Pair>,
+    table: Arc<RwLock<T>>,
+    top: Arc<RwLock<Option<Pair>>>,
+    /// held for the duration of a push so that building and committing a pair is atomic
+    writer: Arc<Mutex<()>>,
+    /// timestamps the headers of pushed entries
+    clock: Arc<Clock>,
+    /// hashes the headers and entries of pushed entries
+    hash_algorithm: Hash,
+    /// keys of the agent owning the chain, signs the headers of pushed entries
+    keys: Option<Keys>,
+}
+
+impl<T: HashTable> Clone for Chain<T> {
+    fn clone(&self) -> Self {
+        Chain {
+            table: self.table(),
+            top: Arc::clone(&self.top),
+            writer: Arc::clone(&self.writer),
+            clock: self.clock(),
+            hash_algorithm: self.hash_algorithm,
+            keys: self.keys(),
+        }
+    }
 }
 
 impl<T: HashTable> PartialEq for Chain<T> {
     fn eq(&self, other: &Chain<T>) -> bool {
-        // an invalid chain is like NaN... not even equal to itself
-        self.validate() &&
-        other.validate() &&
         // header hashing ensures that if the tops match the whole chain matches
+        // comparing doesn't check the integrity of the chains, that is what verify() is for
         self.top() == other.top()
     }
 }
@@ -63,7 +147,7 @@ impl<T: HashTable> Eq for Chain<T> {}
 
 impl<T: HashTable> fmt::Debug for Chain<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Chain {{ top: {:?} }}", self.top)
+        write!(f, "Chain {{ top: {:?} }}", self.top())
     }
 }
 
@@ -79,37 +163,95 @@ impl<T: HashTable> IntoIterator for Chain<T> {
 }
 
 impl<T: HashTable> Chain<T> {
-    // @TODO table implementation is changing anyway so waste of time to mess with ref/value
-    // @see https://github.com/holochain/holochain-rust/issues/135
-    #[allow(unknown_lints)]
-    #[allow(needless_pass_by_value)]
-    /// build a new Chain against an existing HashTable
-    pub fn new(table: Rc<T>) -> Chain<T> {
+    /// build a new Chain against an existing HashTable, timestamping headers with the system clock
+    /// chains built separately against the same table do NOT share a top, clone the chain instead
+    pub fn new(table: Arc<RwLock<T>>) -> Chain<T> {
         Chain {
-            top: None,
-            table: Rc::clone(&table),
+            table,
+            top: Arc::new(RwLock::new(None)),
+            writer: Arc::new(Mutex::new(())),
+            clock: Arc::new(SystemClock {}),
+            hash_algorithm: DEFAULT_HASH,
+            keys: None,
         }
     }
 
-    /// returns a reference to the top Pair
-    pub fn top(&self) -> &Option<Pair> {
-        &self.top
+    /// returns a clone of the chain that timestamps headers with the given clock
+    /// e.g. the clock of the Context the chain is being pushed to from
+    pub fn with_clock(&self, clock: Arc<Clock>) -> Chain<T> {
+        Chain {
+            clock,
+            ..self.clone()
+        }
+    }
+
+    /// returns a reference to the clock that timestamps headers
+    pub fn clock(&self) -> Arc<Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// returns a clone of the chain that hashes pushed headers and entries with the given algorithm
+    /// e.g. the hash algorithm of the DNA
+    pub fn with_hash_algorithm(&self, hash_algorithm: Hash) -> Chain<T> {
+        Chain {
+            hash_algorithm,
+            ..self.clone()
+        }
+    }
+
+    /// returns the algorithm that hashes pushed headers and entries
+    pub fn hash_algorithm(&self) -> Hash {
+        self.hash_algorithm
+    }
+
+    /// returns a clone of the chain owned by the agent with the given keys
+    /// headers of pushed entries are signed by the keys and verify() expects every header to be
+    /// signed by the current key of the agent
+    pub fn with_keys(&self, keys: Keys) -> Chain<T> {
+        Chain {
+            keys: Some(keys),
+            ..self.clone()
+        }
+    }
+
+    /// returns a copy of the keys of the agent owning the chain, if it is owned
+    pub fn keys(&self) -> Option<Keys> {
+        self.keys.clone()
+    }
+
+    /// returns a copy of the top Pair
+    pub fn top(&self) -> Option<Pair> {
+        self.top
+            .read()
+            .expect("owners of the top RwLock shouldn't panic")
+            .clone()
     }
 
     /// returns a reference to the underlying HashTable
-    pub fn table(&self) -> Rc<T> {
-        Rc::clone(&self.table)
+    pub fn table(&self) -> Arc<RwLock<T>> {
+        Arc::clone(&self.table)
     }
 
     /// private pair-oriented version of push() (which expects Entries)
     fn push_pair(&mut self, pair: Pair) -> Result<Pair, HolochainError> {
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
+        self.commit_pair(pair, Vec::new())
+    }
+
+    /// validate the pair against the current top and commit it to the table as the new top
+    /// the pair and any meta are applied to the table as a single transaction
+    /// callers must hold the writer lock
+    fn commit_pair(&self, pair: Pair, metas: Vec<PairMeta>) -> Result<Pair, HolochainError> {
         if !(pair.validate()) {
             return Err(HolochainError::new(
                 "attempted to push an invalid pair for this chain",
             ));
         }
 
-        let top_pair = self.top().as_ref().map(|p| p.key());
+        let top_pair = self.top().map(|p| p.key());
         let next_pair = pair.header().next();
 
         if top_pair != next_pair {
@@ -119,13 +261,28 @@ impl<T: HashTable> Chain<T> {
             )));
         }
 
-        // @TODO implement incubator for thread safety
-        // @see https://github.com/holochain/holochain-rust/issues/135
-        let table = Rc::get_mut(&mut self.table).ok_or(HolochainError::new(
-            "attempted to push while table is already borrowed",
-        ))?;
-        table.commit(&pair)?;
-        self.top = Some(pair.clone());
+        let time = pair.header().timestamp()?;
+        if let Some(top) = self.top() {
+            let top_time = top.header().timestamp()?;
+            if time < top_time {
+                return Err(HolochainError::new(&format!(
+                    "pushed pair time is older than the top pair time: {} vs. {}",
+                    pair.header().time(),
+                    top.header().time(),
+                )));
+            }
+        }
+
+        let mut ops = vec![HashTableOp::Commit(pair.clone())];
+        ops.extend(metas.into_iter().map(HashTableOp::AssertMeta));
+        self.table
+            .write()
+            .expect("owners of the table RwLock shouldn't panic")
+            .apply(&ops)?;
+        *self
+            .top
+            .write()
+            .expect("owners of the top RwLock shouldn't panic") = Some(pair.clone());
         Ok(pair)
     }
 
@@ -134,23 +291,376 @@ impl<T: HashTable> Chain<T> {
     /// Pair to ensure the chain links up correctly across the underlying table data
     /// the newly created and pushed Pair is returned in the fn Result
     pub fn push(&mut self, entry: &Entry) -> Result<Pair, HolochainError> {
+        // the new pair links to the current top so no other push can happen until it is committed
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
         let pair = Pair::new(self, entry.clone());
-        self.push_pair(pair)
+        let pair = match self.keys {
+            Some(ref keys) => pair.sign(keys),
+            None => pair,
+        };
+        self.commit_pair(pair, Vec::new())
+    }
+
+    /// the keys of the agent owning the Chain, attempted describes what needs them for the error
+    /// the logical clock of the keys is moved past every meta they already asserted in the table
+    /// so that metas asserted after reopening a persisted table still sort as the latest
+    fn owner_keys(&self, attempted: &str) -> Result<Keys, HolochainError> {
+        match self.keys {
+            Some(ref keys) => {
+                let last_txn = self
+                    .table
+                    .read()
+                    .expect("owners of the table RwLock shouldn't panic")
+                    .last_txn(&keys.public_key().to_b58())?;
+                keys.observe_txn(last_txn);
+                Ok(keys.clone())
+            }
+            None => Err(HolochainError::new(&format!(
+                "attempted to {} on a chain that is not owned by an agent",
+                attempted
+            ))),
+        }
+    }
+
+    /// errors unless the Pair is in the table and LIVE, only LIVE Pairs can be updated or removed
+    fn check_live(&self, pair: &Pair) -> Result<(), HolochainError> {
+        let table = self
+            .table
+            .read()
+            .expect("owners of the table RwLock shouldn't panic");
+        if table.get(&pair.key())?.is_none() {
+            return Err(HolochainError::new(&format!(
+                "pair {} is not on the chain",
+                pair.key()
+            )));
+        }
+        match table.get_status(pair)? {
+            CRUDStatus::LIVE => Ok(()),
+            status => Err(HolochainError::new(&format!(
+                "pair {} is not live, its status is {:?}",
+                pair.key(),
+                status
+            ))),
+        }
+    }
+
+    /// record an Entry that failed validation without pushing it
+    /// the Pair for the Entry is built against the current top Pair as per push() but the top
+    /// doesn't move, the Pair is only added to the table with its status asserted as REJECTED by
+    /// the agent owning the chain
+    /// the rejected Pair is returned in the fn Result
+    pub fn reject(&mut self, entry: &Entry) -> Result<Pair, HolochainError> {
+        let keys = self.owner_keys("reject an entry")?;
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
+        let pair = Pair::new(self, entry.clone()).sign(&keys);
+        self.table
+            .write()
+            .expect("owners of the table RwLock shouldn't panic")
+            .reject(&keys, &pair)?;
+        Ok(pair)
+    }
+
+    /// push a new version of the Entry of a LIVE Pair on the Chain
+    /// the old Pair is asserted MODIFIED with a crud-link to the new Pair by the agent owning the
+    /// Chain, in the same transaction as the new Pair is committed
+    /// the new Pair is returned in the fn Result
+    pub fn update(&mut self, old_pair: &Pair, entry: &Entry) -> Result<Pair, HolochainError> {
+        let keys = self.owner_keys("update an entry")?;
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
+        self.check_live(old_pair)?;
+        let pair = Pair::new(self, entry.clone()).sign(&keys);
+        let metas = vec![
+            PairMeta::new(
+                &keys,
+                old_pair,
+                STATUS_NAME,
+                &CRUDStatus::MODIFIED.bits().to_string(),
+            ),
+            PairMeta::new(&keys, old_pair, LINK_NAME, &pair.key()),
+        ];
+        self.commit_pair(pair, metas)
+    }
+
+    /// push a deletion record for a LIVE Pair on the Chain
+    /// the Pair is asserted DELETED by the agent owning the Chain, in the same transaction as the
+    /// deletion record is committed
+    /// the Pair of the deletion record is returned in the fn Result
+    pub fn remove(&mut self, pair: &Pair) -> Result<Pair, HolochainError> {
+        let keys = self.owner_keys("remove an entry")?;
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
+        self.check_live(pair)?;
+        let deletion = Pair::new(self, Entry::new(DELETION_ENTRY_TYPE, &pair.key())).sign(&keys);
+        let metas = vec![PairMeta::new(
+            &keys,
+            pair,
+            STATUS_NAME,
+            &CRUDStatus::DELETED.bits().to_string(),
+        )];
+        self.commit_pair(deletion, metas)
+    }
+
+    /// the keys of the agent owning the chain and the Pair of the base Entry of a link
+    fn link_base(&self, entry_hash: &str) -> Result<(Keys, Pair), HolochainError> {
+        let keys = self.owner_keys("link from an entry")?;
+        match self.get_entry(entry_hash)? {
+            Some(base) => Ok((keys, base)),
+            None => Err(HolochainError::new(&format!(
+                "link base {} is not on the chain",
+                entry_hash
+            ))),
+        }
+    }
+
+    /// link an Entry on the Chain to a target Entry with a tag, asserted by the agent owning the
+    /// Chain, @see HashTable::add_link()
+    pub fn add_link(&self, link: &Link) -> Result<(), HolochainError> {
+        let (keys, base) = self.link_base(&link.base)?;
+        self.table
+            .write()
+            .expect("owners of the table RwLock shouldn't panic")
+            .add_link(&keys, &base, &link.tag, &link.target)
+    }
+
+    /// remove a link from an Entry on the Chain with a tombstone, asserted by the agent owning
+    /// the Chain, @see HashTable::remove_link()
+    pub fn remove_link(&self, link: &Link) -> Result<(), HolochainError> {
+        let (keys, base) = self.link_base(&link.base)?;
+        self.table
+            .write()
+            .expect("owners of the table RwLock shouldn't panic")
+            .remove_link(&keys, &base, &link.tag, &link.target)
+    }
+
+    /// rotate the agent keys by pushing a key rotation entry from the old keys to the new keys
+    /// the header of the rotation is signed by the old keys, headers above it must be signed by
+    /// the new keys
+    /// the old keys must be the new keys of the previous rotation, or the keys of the agent owning
+    /// the chain if there is no previous rotation
+    /// an owned chain is owned by the new keys after the rotation, other clones of the chain keep
+    /// the old keys so should be rebuilt with with_keys()
+    /// agents rotate their keys with Action::RotateKeys, which keeps the agent keys and the keys of
+    /// the chain in the agent state together
+    pub fn rotate_keys(
+        &mut self,
+        old_keys: &Keys,
+        new_keys: &Keys,
+    ) -> Result<Pair, HolochainError> {
+        let _writer = self
+            .writer
+            .lock()
+            .expect("owners of the writer Mutex shouldn't panic");
+        let current_key = match self.top_type(KEY_ROTATION_ENTRY_TYPE)? {
+            Some(top_rotation) => Some(KeyRotation::from_entry(top_rotation.entry())?.new_key()),
+            None => self.keys.as_ref().map(|keys| keys.public_key().to_b58()),
+        };
+        if let Some(current_key) = current_key {
+            if current_key != old_keys.public_key().to_b58() {
+                return Err(HolochainError::new(&format!(
+                    "attempted to rotate keys from {} but the current key is {}",
+                    old_keys.public_key().to_b58(),
+                    current_key,
+                )));
+            }
+        }
+        let pair = Pair::new(self, KeyRotation::new(old_keys, new_keys).to_entry()).sign(old_keys);
+        let pair = self.commit_pair(pair, Vec::new())?;
+        if self.keys.is_some() {
+            self.keys = Some(new_keys.clone());
+        }
+        Ok(pair)
     }
 
     /// returns true if all pairs in the chain pass validation
+    /// @see chain.verify()
     pub fn validate(&self) -> bool {
-        self.iter().all(|p| p.validate())
+        self.verify().is_ok()
+    }
+
+    /// walks the whole chain checking that it is intact and untampered
+    /// - every pair is stored in the table under the hash of its header
+    /// - every header entry hash and entry type matches its entry
+    /// - every next links to the hash of the pair below it, and only the bottom pair has no next
+    /// - every type_next links to the hash of the nearest pair of the same type below it
+    /// - every header time is a valid timestamp no older than the time of the pair below it
+    /// - every key rotation is signed by both keys and retires the key of the previous rotation
+    /// - every signed header is signed by the key valid at its position, i.e. the old key of the
+    ///   next rotation above it or the new key of the last rotation below it, a rotation header
+    ///   may be signed by either key
+    /// - if the chain is owned, every header is signed and the current key is the owner's key
+    ///
+    /// the error for the first broken pair counting up from the bottom is returned
+    /// positions count from 0 at the genesis pair
+    pub fn verify(&self) -> Result<(), HolochainError> {
+        // the table may be missing pairs or hold pairs under the wrong key so the chain can't be
+        // walked with iter(), which trusts the table
+        let mut pairs = Vec::new();
+        let mut current = self.top();
+        while let Some(pair) = current {
+            current = match pair.header().next() {
+                Some(next) => match self.get(&next)? {
+                    Some(next_pair) => {
+                        if next_pair.key() != next {
+                            return Err(HolochainError::InvalidChain(format!(
+                                "chain verification failed: pair stored under {} hashes to {}",
+                                next,
+                                next_pair.key(),
+                            )));
+                        }
+                        Some(next_pair)
+                    }
+                    None => {
+                        return Err(HolochainError::InvalidChain(format!(
+                            "chain verification failed: pair {} links to missing pair {}",
+                            pair.key(),
+                            next,
+                        )))
+                    }
+                },
+                None => None,
+            };
+            pairs.push(pair);
+        }
+        pairs.reverse();
+
+        let mut previous: Option<String> = None;
+        let mut previous_time: Option<DateTime<Utc>> = None;
+        let mut type_tops: HashMap<String, String> = HashMap::new();
+        let owner_key = self.keys.as_ref().map(|keys| keys.public_key().to_b58());
+        // headers below the first rotation are signed by its old key, without rotations headers
+        // are signed by the owner
+        let mut current_key = pairs
+            .iter()
+            .filter(|pair| pair.header().entry_type() == KEY_ROTATION_ENTRY_TYPE)
+            .filter_map(|pair| KeyRotation::from_entry(pair.entry()).ok())
+            .next()
+            .map(|rotation| rotation.old_key())
+            .or_else(|| owner_key.clone());
+        for (position, pair) in pairs.iter().enumerate() {
+            let header = pair.header();
+            let entry = pair.entry();
+            let fail = |reason: String| {
+                Err(HolochainError::InvalidChain(format!(
+                    "chain verification failed at position {} (pair {}): {}",
+                    position,
+                    pair.key(),
+                    reason,
+                )))
+            };
+
+            let entry_hash = entry.hash_with(header.hash_algorithm());
+            if header.entry() != entry_hash {
+                return fail(format!(
+                    "header entry hash {} does not match entry hash {}",
+                    header.entry(),
+                    entry_hash,
+                ));
+            }
+            if header.entry_type() != entry.entry_type() {
+                return fail(format!(
+                    "header entry type {} does not match entry type {}",
+                    header.entry_type(),
+                    entry.entry_type(),
+                ));
+            }
+            if header.next() != previous {
+                return fail(format!(
+                    "next {:?} does not match the previous pair {:?}",
+                    header.next(),
+                    previous,
+                ));
+            }
+            let type_previous = type_tops.get(&header.entry_type()).cloned();
+            if header.type_next() != type_previous {
+                return fail(format!(
+                    "type_next {:?} does not match the previous pair of type {} {:?}",
+                    header.type_next(),
+                    header.entry_type(),
+                    type_previous,
+                ));
+            }
+            let time = match header.timestamp() {
+                Ok(time) => time,
+                Err(err) => return fail(err.to_string()),
+            };
+            if previous_time.map_or(false, |previous_time| time < previous_time) {
+                return fail(format!(
+                    "time {} is older than the time of the previous pair {:?}",
+                    header.time(),
+                    previous_time,
+                ));
+            }
+
+            let mut signing_keys: Vec<String> = current_key.iter().cloned().collect();
+            if header.entry_type() == KEY_ROTATION_ENTRY_TYPE {
+                let rotation = match KeyRotation::from_entry(entry) {
+                    Ok(rotation) => rotation,
+                    Err(err) => return fail(err.to_string()),
+                };
+                if !rotation.verify() {
+                    return fail("key rotation is not signed by both keys".into());
+                }
+                if current_key != Some(rotation.old_key()) {
+                    return fail(format!(
+                        "key rotation retires key {} but the current key is {:?}",
+                        rotation.old_key(),
+                        current_key,
+                    ));
+                }
+                signing_keys.push(rotation.new_key());
+                current_key = Some(rotation.new_key());
+            }
+            if header.is_signed() {
+                if !header.verify_signature() {
+                    return fail("header signature is not valid".into());
+                }
+                // headers of unowned chains without rotations may be signed by anyone
+                if !signing_keys.is_empty() && !signing_keys.contains(&header.source()) {
+                    return fail(format!(
+                        "header is signed by {} but the valid keys are {:?}",
+                        header.source(),
+                        signing_keys,
+                    ));
+                }
+            } else if owner_key.is_some() {
+                return fail("header of an owned chain is not signed".into());
+            }
+
+            previous = Some(header.hash());
+            previous_time = Some(time);
+            type_tops.insert(header.entry_type(), header.hash());
+        }
+        if owner_key.is_some() && current_key != owner_key {
+            return Err(HolochainError::InvalidChain(format!(
+                "chain verification failed: chain is owned by {:?} but the current key is {:?}",
+                owner_key, current_key,
+            )));
+        }
+        Ok(())
     }
 
     /// returns a ChainIterator that provides cloned Pairs from the underlying HashTable
     pub fn iter(&self) -> ChainIterator<T> {
-        ChainIterator::new(self.table(), self.top().clone())
+        ChainIterator::new(self.table(), self.top())
     }
 
     /// get a Pair by Pair/Header key from the HashTable if it exists
     pub fn get(&self, k: &str) -> Result<Option<Pair>, HolochainError> {
-        self.table.get(k)
+        self.table
+            .read()
+            .expect("owners of the table RwLock shouldn't panic")
+            .get(k)
     }
 
     /// get an Entry by Entry key from the HashTable if it exists
@@ -161,7 +671,22 @@ impl<T: HashTable> Chain<T> {
                 .iter()
                 // @TODO entry hashes are NOT unique across pairs so k/v lookups can't be 1:1
                 // @see https://github.com/holochain/holochain-rust/issues/145
-                .find(|p| p.entry().hash() == entry_hash))
+                .find(|p| p.header().entry() == entry_hash))
+    }
+
+    /// get the target entry hashes of every live link with the query tag from an Entry on the Chain
+    pub fn get_links(&self, query: &LinkQuery) -> Result<Vec<String>, HolochainError> {
+        match self.get_entry(&query.base)? {
+            Some(base) => self
+                .table
+                .read()
+                .expect("owners of the table RwLock shouldn't panic")
+                .get_links(&base, &query.tag),
+            None => Err(HolochainError::new(&format!(
+                "link base {} is not on the chain",
+                query.base
+            ))),
+        }
     }
 
     /// get the top Pair by Entry type
@@ -169,6 +694,56 @@ impl<T: HashTable> Chain<T> {
         Ok(self.iter().find(|p| p.header().entry_type() == t))
     }
 
+    /// returns a ChainTypeIterator over every Pair of the given Entry type, newest first
+    /// only the pairs above the top Pair of the type are scanned, after that type_next is followed
+    pub fn iter_type(&self, t: &str) -> Result<ChainTypeIterator<T>, HolochainError> {
+        Ok(ChainTypeIterator::new(self.table(), self.top_type(t)?))
+    }
+
+    /// get a page of the Pairs of the given Entry type
+    /// pairs are newest first, or oldest first if reverse is true
+    /// skip pairs are dropped from the start of the results, then at most limit pairs are returned
+    pub fn query_type(
+        &self,
+        t: &str,
+        skip: usize,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> Result<Vec<Pair>, HolochainError> {
+        let limit = limit.unwrap_or(usize::max_value());
+        let iter = self.iter_type(t)?;
+        Ok(if reverse {
+            // there are no links towards newer pairs so every pair of the type must be collected
+            let mut pairs = iter.collect::<Vec<Pair>>();
+            pairs.reverse();
+            pairs.into_iter().skip(skip).take(limit).collect()
+        } else {
+            iter.skip(skip).take(limit).collect()
+        })
+    }
+
+    /// get the Pairs with a header time in the given range, newest first
+    /// since is inclusive, until is exclusive, None leaves that end of the range open
+    pub fn query_time(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Pair>, HolochainError> {
+        let mut pairs = Vec::new();
+        for pair in self.iter() {
+            let time = pair.header().timestamp()?;
+            if until.map_or(false, |until| time >= until) {
+                continue;
+            }
+            // header times never decrease up the chain so everything below is too old as well
+            if since.map_or(false, |since| time < since) {
+                break;
+            }
+            pairs.push(pair);
+        }
+        Ok(pairs)
+    }
+
     /// get the entire chain, top to bottom as a JSON array or canonical pairs
     /// @TODO return canonical JSON
     /// @see https://github.com/holochain/holochain-rust/issues/75
@@ -178,42 +753,65 @@ impl<T: HashTable> Chain<T> {
     }
 
     /// restore canonical JSON chain
-    ///
-    /// # Panics
-    ///
-    /// Panics if the string passed isn't valid JSON or pairs fail to validate
+    /// errors if the string passed isn't valid JSON, pairs fail to validate or the restored chain
+    /// fails verification, e.g. the JSON was tampered with
     ///
     /// @TODO accept canonical JSON
     /// @see https://github.com/holochain/holochain-rust/issues/75
-    pub fn from_json(table: Rc<T>, s: &str) -> Self {
-        // @TODO inappropriate expect?
-        // @see https://github.com/holochain/holochain-rust/issues/168
-        let mut as_seq: Vec<Pair> = serde_json::from_str(s).expect("argument should be valid json");
+    pub fn from_json(table: Arc<RwLock<T>>, s: &str) -> Result<Self, HolochainError> {
+        let mut as_seq: Vec<Pair> = serde_json::from_str(s)?;
         as_seq.reverse();
 
         let mut chain = Chain::new(table);
         for p in as_seq {
-            chain.push_pair(p).expect("pair should be valid");
+            chain.push_pair(p)?;
         }
-        chain
+        chain.verify()?;
+        Ok(chain)
     }
 }
 
 #[cfg(test)]
 pub mod tests {
 
-    use super::Chain;
+    use super::{Chain, ChainIterator, ChainTypeIterator, DELETION_ENTRY_TYPE};
+    use agent::{
+        keys::{
+            tests::{test_keys, test_keys_b},
+            Keys,
+        },
+        rotation::{tests::test_keys_c, KeyRotation, KEY_ROTATION_ENTRY_TYPE},
+    };
+    use chrono::Duration;
+    use clock::tests::{test_clock, test_time};
+    use error::HolochainError;
+    use hash::algorithm_of;
     use hash_table::{
-        entry::tests::{test_entry, test_entry_a, test_entry_b, test_type_a, test_type_b},
+        entry::{
+            tests::{test_entry, test_entry_a, test_entry_b, test_type, test_type_a, test_type_b},
+            Entry,
+        },
+        file::{tests::test_table as test_file_table, FileTable},
+        links::{
+            tests::{test_link, test_link_query},
+            Link,
+        },
         memory::{tests::test_table, MemTable},
         pair::Pair,
+        status::CRUDStatus,
         HashTable,
     };
-    use std::rc::Rc;
+    use multihash::Hash;
+    use serde_json;
+    use std::{
+        fs,
+        sync::{Arc, RwLock},
+        thread,
+    };
 
-    /// builds a dummy chain for testing
+    /// builds a dummy chain for testing, timestamping headers with test_clock()
     pub fn test_chain() -> Chain<MemTable> {
-        Chain::new(Rc::new(test_table()))
+        Chain::new(Arc::new(RwLock::new(test_table()))).with_clock(Arc::new(test_clock()))
     }
 
     #[test]
@@ -244,13 +842,25 @@ pub mod tests {
 
         assert_ne!(c1, c3);
         assert_ne!(c2, c3);
+
+        // comparing doesn't verify, e.g. a clone left with the old keys after a rotation
+        let mut owned = test_chain().with_keys(test_keys());
+        owned
+            .push(&e1)
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let stale = owned.clone();
+        owned
+            .rotate_keys(&test_keys(), &test_keys_b())
+            .expect("rotating the current keys shouldn't fail");
+        assert!(stale.verify().is_err());
+        assert_eq!(owned, stale);
     }
 
     #[test]
     /// tests for chain.top()
     fn top() {
         let mut chain = test_chain();
-        assert_eq!(&None, chain.top());
+        assert_eq!(None, chain.top());
 
         let e1 = test_entry_a();
         let e2 = test_entry_b();
@@ -258,34 +868,113 @@ pub mod tests {
         let p1 = chain
             .push(&e1)
             .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
-        assert_eq!(&Some(p1), chain.top());
+        assert_eq!(Some(p1), chain.top());
 
         let p2 = chain
             .push(&e2)
             .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
-        assert_eq!(&Some(p2), chain.top());
+        assert_eq!(Some(p2), chain.top());
     }
 
     #[test]
     /// tests for chain.table()
     fn table() {
-        let t = test_table();
-        let mut c = Chain::new(Rc::new(t));
+        let t = Arc::new(RwLock::new(test_table()));
+        let mut c = Chain::new(Arc::clone(&t));
         // test that adding something to the chain adds to the table
         let p = c
             .push(&test_entry())
             .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
-        let tr = Rc::new(c.table());
         let chain_entry = c
             .table()
+            .read()
+            .unwrap()
             .get(&p.key())
             .expect("getting an entry from a chain shouldn't fail");
         assert_eq!(Some(&p), chain_entry.as_ref());
-        let tr_entry = tr
+        // the table is shared with the chain, not copied into it
+        let t_entry = t
+            .read()
+            .unwrap()
             .get(&p.key())
             .expect("getting an entry from a chain shouldn't fail");
-        assert_eq!(Some(&p), tr_entry.as_ref());
-        assert_eq!(chain_entry, tr_entry);
+        assert_eq!(Some(&p), t_entry.as_ref());
+        assert_eq!(chain_entry, t_entry);
+    }
+
+    #[test]
+    /// test that clones of a chain share the same table
+    fn clone() {
+        let mut c1 = test_chain();
+        let c2 = c1.clone();
+
+        let p = c1
+            .push(&test_entry())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        assert_eq!(
+            Some(&p),
+            c2.get(&p.key())
+                .expect("getting an entry from a chain shouldn't fail")
+                .as_ref()
+        );
+        // the top is shared too
+        assert_eq!(Some(p), c2.top());
+    }
+
+    #[test]
+    /// test that chains and their iterators can be shared across threads
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Chain<MemTable>>();
+        assert_send_sync::<ChainIterator<MemTable>>();
+        assert_send_sync::<ChainTypeIterator<MemTable>>();
+    }
+
+    #[test]
+    /// test that a chain can be pushed to while it is being iterated
+    fn push_while_iterating() {
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to a chain shouldn't fail");
+
+        let mut iter = chain.iter();
+        let p2 = chain
+            .push(&test_entry_b())
+            .expect("pushing to a chain with a live iterator shouldn't fail");
+
+        // the iterator still walks the chain as it was when the iterator was built
+        assert_eq!(Some(test_entry_a()), iter.next().map(|p| p.entry().clone()));
+        assert_eq!(None, iter.next());
+        assert_eq!(Some(p2), chain.top());
+    }
+
+    #[test]
+    /// test that concurrent pushes from clones on different threads are serialized
+    fn push_concurrent() {
+        let chain = test_chain();
+
+        let handles = (0..10)
+            .map(|i| {
+                let mut c = chain.clone();
+                thread::spawn(move || {
+                    c.push(&Entry::new(&test_type(), &i.to_string()))
+                        .expect("pushing a valid entry to a shared chain shouldn't fail")
+                })
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            h.join().expect("pushing threads shouldn't panic");
+        }
+
+        // every push landed and the headers still link up
+        assert_eq!(10, chain.iter().count());
+        assert!(chain.validate());
+        let pairs = chain.iter().collect::<Vec<Pair>>();
+        for w in pairs.windows(2) {
+            assert_eq!(Some(w[1].key()), w[0].header().next());
+        }
     }
 
     #[test]
@@ -293,7 +982,7 @@ pub mod tests {
     fn push() {
         let mut chain = test_chain();
 
-        assert_eq!(&None, chain.top());
+        assert_eq!(None, chain.top());
 
         // chain top, pair entry and headers should all line up after a push
         let e1 = test_entry_a();
@@ -337,6 +1026,536 @@ pub mod tests {
         assert!(chain.validate());
     }
 
+    /// asserts that the chain fails verification with an error containing the given reason
+    fn assert_invalid<T: HashTable>(chain: &Chain<T>, reason: &str) {
+        match chain.verify() {
+            Err(HolochainError::InvalidChain(message)) => {
+                assert!(message.contains(reason), "unexpected error: {}", message)
+            }
+            other => panic!("expected an invalid chain but got {:?}", other),
+        }
+        assert!(!chain.validate());
+    }
+
+    /// round trips a pair through JSON with a single header field replaced
+    fn tamper_header(pair: &Pair, field: &str, value: serde_json::Value) -> Pair {
+        let mut json = serde_json::to_value(pair).expect("pairs should serialize");
+        json["header"][field] = value;
+        serde_json::from_value(json).expect("tampered pairs should deserialize")
+    }
+
+    #[test]
+    /// test chain.verify() on intact chains
+    fn verify() {
+        let mut chain = test_chain();
+        assert_eq!(Ok(()), chain.verify());
+
+        for e in &[
+            test_entry_a(),
+            test_entry_b(),
+            test_entry_a(),
+            test_entry_b(),
+        ] {
+            chain
+                .push(e)
+                .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+            assert_eq!(Ok(()), chain.verify());
+        }
+    }
+
+    #[test]
+    /// a pair with a broken type_next is reported at its position
+    fn verify_type_next() {
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p3 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        // the tampered top still links to the pair below it so push() can't catch it
+        *chain.top.write().unwrap() =
+            Some(tamper_header(&p3, "type_next", serde_json::Value::Null));
+        assert_invalid(&chain, "position 2");
+        assert_invalid(&chain, "type_next None");
+    }
+
+    #[test]
+    /// a pair with a time older than the pair below it is reported at its position
+    fn verify_time() {
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        let earlier = (test_time() - Duration::seconds(1)).to_rfc3339();
+        *chain.top.write().unwrap() = Some(tamper_header(&p2, "time", earlier.into()));
+        assert_invalid(&chain, "position 1");
+        assert_invalid(&chain, "is older than the time of the previous pair");
+
+        *chain.top.write().unwrap() = Some(tamper_header(&p2, "time", "yesterday".into()));
+        assert_invalid(&chain, "position 1");
+        assert_invalid(&chain, "is not an ISO8601 timestamp");
+    }
+
+    /// pushes an entry with its header signed by the given keys
+    fn push_signed<T: HashTable>(chain: &mut Chain<T>, entry: &Entry, keys: &Keys) -> Pair {
+        let pair = Pair::new(chain, entry.clone()).sign(keys);
+        chain
+            .push_pair(pair)
+            .expect("pushing a valid pair to an exlusively owned chain shouldn't fail")
+    }
+
+    #[test]
+    /// test chain.rotate_keys()
+    fn rotate_keys() {
+        let mut chain = test_chain();
+        push_signed(&mut chain, &test_entry_a(), &test_keys());
+
+        let rotation = chain
+            .rotate_keys(&test_keys(), &test_keys_b())
+            .expect("rotating the current keys shouldn't fail");
+        assert_eq!(Some(rotation.clone()), chain.top());
+        assert_eq!(
+            Ok(KeyRotation::new(&test_keys(), &test_keys_b())),
+            KeyRotation::from_entry(rotation.entry())
+        );
+        assert_eq!(
+            test_keys().public_key().to_b58(),
+            rotation.header().source()
+        );
+
+        push_signed(&mut chain, &test_entry_b(), &test_keys_b());
+        assert_eq!(Ok(()), chain.verify());
+
+        // only the current keys can be rotated
+        assert!(chain.rotate_keys(&test_keys(), &test_keys_c()).is_err());
+        chain
+            .rotate_keys(&test_keys_b(), &test_keys_c())
+            .expect("rotating the current keys shouldn't fail");
+        push_signed(&mut chain, &test_entry_a(), &test_keys_c());
+        assert_eq!(Ok(()), chain.verify());
+    }
+
+    #[test]
+    /// test that owned chains sign the headers of pushed entries
+    fn push_keys() {
+        let mut chain = test_chain();
+        assert_eq!(None, chain.keys());
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert!(!p1.header().is_signed());
+
+        let mut chain = test_chain().with_keys(test_keys());
+        assert_eq!(Some(test_keys()), chain.keys());
+        for e in &[test_entry_a(), test_entry_b()] {
+            let pair = chain
+                .push(e)
+                .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+            assert_eq!(test_keys().public_key().to_b58(), pair.header().source());
+            assert!(pair.header().verify_signature());
+        }
+        assert_eq!(Ok(()), chain.verify());
+
+        // rotating the keys of an owned chain changes the owner
+        chain
+            .rotate_keys(&test_keys(), &test_keys_b())
+            .expect("rotating the current keys shouldn't fail");
+        assert_eq!(Some(test_keys_b()), chain.keys());
+        let pair = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert_eq!(test_keys_b().public_key().to_b58(), pair.header().source());
+        assert_eq!(Ok(()), chain.verify());
+    }
+
+    #[test]
+    /// every header of an owned chain must be signed by the owner
+    fn verify_keys() {
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert_eq!(Ok(()), chain.verify());
+        assert_invalid(&chain.with_keys(test_keys()), "position 0");
+        assert_invalid(
+            &chain.with_keys(test_keys()),
+            "header of an owned chain is not signed",
+        );
+
+        let mut chain = test_chain().with_keys(test_keys());
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert_invalid(&chain.with_keys(test_keys_b()), "position 0");
+        assert_invalid(&chain.with_keys(test_keys_b()), "but the valid keys are");
+
+        // the owner must hold the key of the last rotation
+        chain
+            .rotate_keys(&test_keys(), &test_keys_b())
+            .expect("rotating the current keys shouldn't fail");
+        assert_eq!(Ok(()), chain.verify());
+        assert_invalid(&chain.with_keys(test_keys()), "chain is owned by Some(\"");
+
+        // an unowned chain still checks signatures
+        let p2 = chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let unowned = Chain::new(chain.table());
+        let later = (test_time() + Duration::seconds(1)).to_rfc3339();
+        *unowned.top.write().unwrap() = Some(tamper_header(&p2, "time", later.into()));
+        assert_invalid(&unowned, "header signature is not valid");
+    }
+
+    #[test]
+    /// test chain.reject()
+    fn reject() {
+        let mut chain = test_chain();
+        assert!(chain.reject(&test_entry_a()).is_err());
+
+        let mut chain = test_chain().with_keys(test_keys());
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let rejected = chain
+            .reject(&test_entry_b())
+            .expect("rejecting an entry on an owned chain shouldn't fail");
+
+        // the rejected pair links to the top but isn't part of the chain
+        assert_eq!(Some(p1.key()), rejected.header().next());
+        assert!(rejected.header().verify_signature());
+        assert_eq!(Some(p1.clone()), chain.top());
+        assert_eq!(vec![p1], chain.iter().collect::<Vec<Pair>>());
+        assert_eq!(Ok(()), chain.verify());
+
+        // the rejected pair is only in the table, marked REJECTED
+        let table = chain.table();
+        let table = table.read().unwrap();
+        assert_eq!(Ok(Some(rejected.clone())), table.get(&rejected.key()));
+        assert_eq!(Ok(CRUDStatus::REJECTED), table.get_status(&rejected));
+    }
+
+    #[test]
+    /// tests for chain.update()
+    fn update() {
+        let mut chain = test_chain();
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert!(chain.update(&p1, &test_entry_b()).is_err());
+
+        let mut chain = test_chain().with_keys(test_keys());
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .update(&p1, &test_entry_b())
+            .expect("updating a live pair on an owned chain shouldn't fail");
+
+        // the new version is pushed and the old version links to it
+        assert_eq!(Some(p2.clone()), chain.top());
+        assert_eq!(Some(p1.key()), p2.header().next());
+        assert_eq!(Ok(()), chain.verify());
+        {
+            let table = chain.table();
+            let table = table.read().unwrap();
+            assert_eq!(Ok(CRUDStatus::MODIFIED), table.get_status(&p1));
+            assert_eq!(Ok(CRUDStatus::LIVE), table.get_status(&p2));
+            assert_eq!(
+                Ok(Some(p2.clone())),
+                table.get_latest(&p1.key(), CRUDStatus::LIVE)
+            );
+        }
+
+        // only live pairs can be updated
+        assert!(chain.update(&p1, &test_entry()).is_err());
+        let other = test_chain()
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert!(chain.update(&other, &test_entry()).is_err());
+        assert_eq!(Some(p2), chain.top());
+    }
+
+    #[test]
+    /// tests for chain.remove()
+    fn remove() {
+        let mut chain = test_chain();
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert!(chain.remove(&p1).is_err());
+
+        let mut chain = test_chain().with_keys(test_keys());
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let deletion = chain
+            .remove(&p1)
+            .expect("removing a live pair on an owned chain shouldn't fail");
+
+        // the deletion record is pushed and the pair is deleted
+        assert_eq!(Some(deletion.clone()), chain.top());
+        assert_eq!(DELETION_ENTRY_TYPE, deletion.entry().entry_type());
+        assert_eq!(p1.key(), deletion.entry().content());
+        assert_eq!(Ok(()), chain.verify());
+        assert_eq!(
+            Ok(CRUDStatus::DELETED),
+            chain.table().read().unwrap().get_status(&p1)
+        );
+
+        // only live pairs can be removed
+        assert!(chain.remove(&p1).is_err());
+        assert!(chain.update(&p1, &test_entry_b()).is_err());
+        assert_eq!(Some(deletion), chain.top());
+    }
+
+    #[test]
+    /// tests for chain.add_link(), chain.remove_link() and chain.get_links()
+    fn links() {
+        let link = test_link();
+        let query = test_link_query();
+
+        // only an owned chain can link
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert!(chain.add_link(&link).is_err());
+
+        let mut chain = test_chain().with_keys(test_keys());
+        // the base must be on the chain
+        assert!(chain.add_link(&link).is_err());
+        assert!(chain.get_links(&query).is_err());
+
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        assert_eq!(Ok(vec![]), chain.get_links(&query));
+
+        chain.add_link(&link).expect("linking shouldn't fail");
+        assert_eq!(Ok(vec![link.target.clone()]), chain.get_links(&query));
+
+        let other = Link::new(&link.base, &test_entry().hash(), &link.tag);
+        chain.add_link(&other).expect("linking shouldn't fail");
+        assert_eq!(
+            Ok(vec![link.target.clone(), other.target.clone()]),
+            chain.get_links(&query)
+        );
+
+        chain.remove_link(&link).expect("unlinking shouldn't fail");
+        assert_eq!(Ok(vec![other.target.clone()]), chain.get_links(&query));
+        // links are meta, the chain itself doesn't change
+        assert_eq!(1, chain.iter().count());
+        assert_eq!(Ok(()), chain.verify());
+    }
+
+    #[test]
+    /// metas asserted after reopening a persisted table with freshly loaded keys are the latest
+    fn links_reopen() {
+        let link = test_link();
+        let (table, dir) = test_file_table();
+        let mut chain = Chain::new(Arc::new(RwLock::new(table)))
+            .with_clock(Arc::new(test_clock()))
+            .with_keys(test_keys());
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        chain.add_link(&link).expect("linking shouldn't fail");
+        chain.remove_link(&link).expect("unlinking shouldn't fail");
+        chain.add_link(&link).expect("linking shouldn't fail");
+
+        // the keys start a new logical clock
+        let table = FileTable::new(dir.path()).expect("should be able to reopen a FileTable");
+        let json = chain.to_json().expect("chain shouldn't fail to serialize");
+        let chain = Chain::from_json(Arc::new(RwLock::new(table)), &json)
+            .expect("a pushed chain should restore")
+            .with_keys(test_keys());
+        chain.remove_link(&link).expect("unlinking shouldn't fail");
+        assert_eq!(Ok(vec![]), chain.get_links(&test_link_query()));
+    }
+
+    #[test]
+    /// headers must be signed by the key valid at their position
+    fn verify_key_rotation() {
+        let mut chain = test_chain();
+        push_signed(&mut chain, &test_entry_a(), &test_keys());
+        // the rotation itself may be signed by the new key
+        let rotation = push_signed(
+            &mut chain,
+            &KeyRotation::new(&test_keys(), &test_keys_b()).to_entry(),
+            &test_keys_b(),
+        );
+        assert_eq!(Ok(()), chain.verify());
+
+        // the old key isn't valid after the rotation
+        push_signed(&mut chain, &test_entry_b(), &test_keys());
+        assert_invalid(&chain, "position 2");
+        assert_invalid(&chain, "but the valid keys are");
+
+        // the new key isn't valid before the rotation
+        let mut chain = test_chain();
+        push_signed(&mut chain, &test_entry_a(), &test_keys_b());
+        chain
+            .rotate_keys(&test_keys(), &test_keys_b())
+            .expect("rotating keys shouldn't fail");
+        assert_invalid(&chain, "position 0");
+        assert_invalid(&chain, "but the valid keys are");
+
+        // rotations must be signed by both keys
+        let mut chain = test_chain();
+        let mut json = serde_json::to_value(KeyRotation::new(&test_keys(), &test_keys_b()))
+            .expect("rotations should serialize");
+        json["new_key"] = test_keys_c().public_key().to_b58().into();
+        push_signed(
+            &mut chain,
+            &Entry::new(KEY_ROTATION_ENTRY_TYPE, &json.to_string()),
+            &test_keys(),
+        );
+        assert_invalid(&chain, "position 0");
+        assert_invalid(&chain, "key rotation is not signed by both keys");
+
+        // rotations must retire the current key
+        let mut chain = test_chain();
+        push_signed(&mut chain, rotation.entry(), &test_keys());
+        push_signed(
+            &mut chain,
+            &KeyRotation::new(&test_keys(), &test_keys_c()).to_entry(),
+            &test_keys(),
+        );
+        assert_invalid(&chain, "position 1");
+        assert_invalid(&chain, "but the current key is");
+    }
+
+    #[test]
+    /// a pair whose entry doesn't match its header is reported at its position
+    fn verify_entry() {
+        let (table, dir) = test_file_table();
+        let mut chain = Chain::new(Arc::new(RwLock::new(table)));
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        let mut json = serde_json::to_value(&p2).expect("pairs should serialize");
+        json["entry"]["content"] = "tampered content".into();
+        fs::write(
+            dir.path().join("pairs").join(format!("{}.json", p2.key())),
+            json.to_string(),
+        ).expect("should be able to overwrite a pair file");
+
+        assert_invalid(&chain, "position 1");
+        assert_invalid(&chain, "does not match entry hash");
+    }
+
+    #[test]
+    /// a pair stored under a key that isn't its header hash is reported
+    fn verify_header_hash() {
+        let (table, dir) = test_file_table();
+        let mut chain = Chain::new(Arc::new(RwLock::new(table)));
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        let tampered = tamper_header(&p1, "time", "tampered time".into());
+        fs::write(
+            dir.path().join("pairs").join(format!("{}.json", p1.key())),
+            serde_json::to_string(&tampered).expect("pairs should serialize"),
+        ).expect("should be able to overwrite a pair file");
+
+        assert_invalid(
+            &chain,
+            &format!("stored under {} hashes to {}", p1.key(), tampered.key()),
+        );
+    }
+
+    #[test]
+    /// a pair linking to a pair that isn't in the table is reported
+    fn verify_missing() {
+        let (table, dir) = test_file_table();
+        let mut chain = Chain::new(Arc::new(RwLock::new(table)));
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        fs::remove_file(dir.path().join("pairs").join(format!("{}.json", p1.key())))
+            .expect("should be able to remove a pair file");
+
+        assert_invalid(&chain, &format!("links to missing pair {}", p1.key()));
+    }
+
+    #[test]
+    /// test that pushed headers are timestamped by the chain clock
+    fn push_time() {
+        let clock = Arc::new(test_clock());
+        let mut chain = test_chain().with_clock(clock.clone());
+
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        clock.advance(Duration::seconds(1));
+        let p2 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        assert_eq!(Ok(test_time()), p1.header().timestamp());
+        assert_eq!(
+            Ok(test_time() + Duration::seconds(1)),
+            p2.header().timestamp()
+        );
+
+        // a clock running backwards can't push older headers
+        clock.set(test_time());
+        assert!(chain.push(&test_entry_a()).is_err());
+        assert_eq!(Some(p2), chain.top());
+    }
+
+    #[test]
+    /// test that pushed headers and entries are hashed with the chain hash algorithm
+    fn push_hash_algorithm() {
+        let mut chain = test_chain().with_hash_algorithm(Hash::SHA3256);
+        assert_eq!(Hash::SHA3256, chain.hash_algorithm());
+
+        let e = test_entry_a();
+        let p1 = chain
+            .push(&e)
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .push(&e)
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        assert_eq!(e.hash_with(Hash::SHA3256), p1.header().entry());
+        assert_ne!(e.hash(), p1.header().entry());
+        assert_eq!(Hash::SHA3256, p1.header().hash_algorithm());
+        assert_eq!(Some(Hash::SHA3256), algorithm_of(&p1.key()));
+        assert_eq!(Some(p1.key()), p2.header().next());
+        assert_eq!(Ok(()), chain.verify());
+        assert_eq!(
+            Some(p2),
+            chain
+                .get_entry(&p1.header().entry())
+                .expect("getting an entry from a chain shouldn't fail")
+        );
+    }
+
     #[test]
     /// test chain.push() and chain.get() together
     fn round_trip() {
@@ -642,14 +1861,175 @@ pub mod tests {
             .push(&e3)
             .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
 
-        let expected_json = r#"[{"header":{"entry_type":"testEntryType","time":"","next":"QmPT5HXvyv54Dg36YSK1A2rYvoPCNWoqpLzzZnHnQBcU6x","entry":"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT","type_next":"QmawqBCVVap9KdaakqEHF4JzUjjLhmR7DpM5jgJko8j1rA","signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}},{"header":{"entry_type":"testEntryTypeB","time":"","next":"QmawqBCVVap9KdaakqEHF4JzUjjLhmR7DpM5jgJko8j1rA","entry":"QmPz5jKXsxq7gPVAbPwx5gD2TqHfqB8n25feX5YH18JXrT","type_next":null,"signature":""},"entry":{"content":"other test entry content","entry_type":"testEntryTypeB"}},{"header":{"entry_type":"testEntryType","time":"","next":null,"entry":"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT","type_next":null,"signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}}]"#;
+        let expected_json = r#"[{"header":{"entry_type":"testEntryType","time":"2018-08-01T00:00:00+00:00","next":"QmUCZpfuPVfHfjXkeR4TAuYVDdDpdp15ZJhXXtYJTMseVp","entry":"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj","type_next":"QmPkFAR2pN8WresrQFPBBRHn9hxLTZiGPUZAY7bqyz3M11","source":"","signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}},{"header":{"entry_type":"testEntryTypeB","time":"2018-08-01T00:00:00+00:00","next":"QmPkFAR2pN8WresrQFPBBRHn9hxLTZiGPUZAY7bqyz3M11","entry":"QmbtribVHRvQWHo26rZk5cN27g5ZhbRhHebcCvwfnakcuM","type_next":null,"source":"","signature":""},"entry":{"content":"other test entry content","entry_type":"testEntryTypeB"}},{"header":{"entry_type":"testEntryType","time":"2018-08-01T00:00:00+00:00","next":null,"entry":"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj","type_next":null,"source":"","signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}}]"#;
         assert_eq!(
             expected_json,
             chain.to_json().expect("chain shouldn't fail to serialize")
         );
 
-        let table = test_table();
-        assert_eq!(chain, Chain::from_json(Rc::new(table), expected_json));
+        let table = Arc::new(RwLock::new(test_table()));
+        assert_eq!(Ok(chain), Chain::from_json(table, expected_json));
     }
 
+    #[test]
+    /// test that from_json() rejects a chain with a tampered top
+    fn from_json_tampered() {
+        let mut chain = test_chain();
+        chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        let mut pairs = chain.iter().collect::<Vec<Pair>>();
+        pairs[0] = tamper_header(&p2, "type_next", serde_json::Value::Null);
+        let json = serde_json::to_string(&pairs).expect("pairs should serialize");
+
+        match Chain::from_json(Arc::new(RwLock::new(test_table())), &json) {
+            Err(HolochainError::InvalidChain(_)) => (),
+            result => panic!("restored a tampered chain: {:?}", result),
+        }
+        assert!(Chain::from_json(Arc::new(RwLock::new(test_table())), "not json").is_err());
+    }
+
+    #[test]
+    /// test chain.query_time()
+    fn query_time() {
+        let clock = Arc::new(test_clock());
+        let mut chain = test_chain().with_clock(clock.clone());
+
+        // one pair per second, two pairs in the last second
+        let mut pairs = Vec::new();
+        for i in 0..4 {
+            pairs.push(
+                chain
+                    .push(&Entry::new(&test_type(), &i.to_string()))
+                    .expect("pushing a valid entry to an exlusively owned chain shouldn't fail"),
+            );
+            if i < 2 {
+                clock.advance(Duration::seconds(1));
+            }
+        }
+        pairs.reverse();
+        let seconds = |s| Some(test_time() + Duration::seconds(s));
+
+        // open ranges
+        assert_eq!(Ok(pairs.clone()), chain.query_time(None, None));
+        assert_eq!(Ok(pairs[..2].to_vec()), chain.query_time(seconds(2), None));
+        assert_eq!(Ok(pairs[2..].to_vec()), chain.query_time(None, seconds(2)));
+
+        // since is inclusive, until is exclusive
+        assert_eq!(
+            Ok(pairs[2..3].to_vec()),
+            chain.query_time(seconds(1), seconds(2))
+        );
+
+        // empty ranges
+        assert_eq!(Ok(vec![]), chain.query_time(seconds(3), None));
+        assert_eq!(Ok(vec![]), chain.query_time(None, seconds(0)));
+        assert_eq!(Ok(vec![]), chain.query_time(seconds(1), seconds(1)));
+    }
+
+    #[test]
+    /// test chain.iter_type()
+    fn iter_type() {
+        let mut chain = test_chain();
+
+        assert_eq!(
+            0,
+            chain
+                .iter_type(&test_type_a())
+                .expect("iterating over a type shouldn't fail")
+                .count()
+        );
+
+        let p1 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p2 = chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p3 = chain
+            .push(&test_entry_a())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+        let p4 = chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        assert_eq!(
+            vec![p3, p1],
+            chain
+                .iter_type(&test_type_a())
+                .expect("iterating over a type shouldn't fail")
+                .collect::<Vec<Pair>>()
+        );
+        assert_eq!(
+            vec![p4, p2],
+            chain
+                .iter_type(&test_type_b())
+                .expect("iterating over a type shouldn't fail")
+                .collect::<Vec<Pair>>()
+        );
+        assert_eq!(
+            0,
+            chain
+                .iter_type("unknownType")
+                .expect("iterating over a type shouldn't fail")
+                .count()
+        );
+    }
+
+    #[test]
+    /// test chain.query_type()
+    fn query_type() {
+        let mut chain = test_chain();
+
+        let pairs = (0..5)
+            .map(|i| {
+                chain
+                    .push(&Entry::new(&test_type_a(), &i.to_string()))
+                    .expect("pushing a valid entry to an exlusively owned chain shouldn't fail")
+            })
+            .collect::<Vec<Pair>>();
+        // interleave another type so type_next and next differ
+        chain
+            .push(&test_entry_b())
+            .expect("pushing a valid entry to an exlusively owned chain shouldn't fail");
+
+        let mut newest_first = pairs.clone();
+        newest_first.reverse();
+
+        // everything
+        assert_eq!(
+            Ok(newest_first.clone()),
+            chain.query_type(&test_type_a(), 0, None, false)
+        );
+        assert_eq!(
+            Ok(pairs.clone()),
+            chain.query_type(&test_type_a(), 0, None, true)
+        );
+
+        // pages
+        assert_eq!(
+            Ok(newest_first[1..3].to_vec()),
+            chain.query_type(&test_type_a(), 1, Some(2), false)
+        );
+        assert_eq!(
+            Ok(pairs[1..3].to_vec()),
+            chain.query_type(&test_type_a(), 1, Some(2), true)
+        );
+        assert_eq!(
+            Ok(newest_first[4..].to_vec()),
+            chain.query_type(&test_type_a(), 4, Some(2), false)
+        );
+
+        // out of range
+        assert_eq!(Ok(vec![]), chain.query_type(&test_type_a(), 5, None, false));
+        assert_eq!(
+            Ok(vec![]),
+            chain.query_type(&test_type_a(), 0, Some(0), true)
+        );
+        assert_eq!(Ok(vec![]), chain.query_type("unknownType", 0, None, false));
+    }
 }
@@ -0,0 +1,95 @@
+//! The clock is the source of every timestamp a Holochain instance writes, e.g. chain header times.
+//! It is attached to the Context so that tests can swap the system clock for one they control.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+
+/// trait that defines the time functionality that holochain_core requires
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// reads the time from the system
+#[derive(Clone, Default)]
+pub struct SystemClock {}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// only moves when told to, useful for deterministic tests
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+            .now
+            .lock()
+            .expect("owners of the clock Mutex shouldn't panic")
+    }
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        ManualClock {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// sets the time returned by now()
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self
+            .now
+            .lock()
+            .expect("owners of the clock Mutex shouldn't panic") = now;
+    }
+
+    /// moves the time returned by now() on by the given duration
+    pub fn advance(&self, duration: Duration) {
+        let now = self.now() + duration;
+        self.set(now);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{Clock, ManualClock, SystemClock};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    /// the time every test clock starts at
+    pub fn test_time() -> DateTime<Utc> {
+        Utc.ymd(2018, 8, 1).and_hms(0, 0, 0)
+    }
+
+    /// builds a ManualClock set to test_time()
+    pub fn test_clock() -> ManualClock {
+        ManualClock::new(test_time())
+    }
+
+    #[test]
+    /// the system clock moves forward
+    fn system_clock() {
+        let clock = SystemClock {};
+        let t1 = clock.now();
+        let t2 = clock.now();
+        assert!(t1 <= t2);
+    }
+
+    #[test]
+    /// a manual clock only moves when set or advanced
+    fn manual_clock() {
+        let clock = test_clock();
+        assert_eq!(test_time(), clock.now());
+        assert_eq!(test_time(), clock.now());
+
+        clock.advance(Duration::seconds(5));
+        assert_eq!(test_time() + Duration::seconds(5), clock.now());
+
+        clock.set(test_time());
+        assert_eq!(test_time(), clock.now());
+    }
+}
@@ -1,8 +1,13 @@
+use agent::keys::Keys;
+use clock::Clock;
 use error::HolochainError;
 use holochain_agent::Agent;
 use logger::Logger;
 use persister::Persister;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 /// Context holds those aspects of the outside world that a Holochain instance needs to operate
 #[derive(Clone)]
@@ -10,6 +15,14 @@ pub struct Context {
     pub agent: Agent,
     pub logger: Arc<Mutex<Logger>>,
     pub persister: Arc<Mutex<Persister>>,
+    pub clock: Arc<Clock>,
+    /// keys for the agent, e.g. loaded from a keystore::Keystore
+    /// an agent without keys gets a new keypair when the application is initialized
+    pub keys: Option<Keys>,
+    /// keys the agent can rotate to, by public key
+    /// Action::RotateKeys only carries the new public key so private keys stay out of actions and
+    /// everything persisted with them
+    pub rotation_keys: Arc<Mutex<HashMap<String, Keys>>>,
 }
 
 impl Context {
@@ -19,4 +32,35 @@ impl Context {
         logger.log(msg.to_string());
         Ok(())
     }
+
+    /// makes the keys available to Action::RotateKeys, returns the public key to rotate to
+    pub fn add_rotation_keys(&self, keys: Keys) -> Result<String, HolochainError> {
+        let public_key = keys.public_key().to_b58();
+        self.rotation_keys
+            .lock()
+            .map_err(|_| HolochainError::new("the rotation keys lock is poisoned"))?
+            .insert(public_key.clone(), keys);
+        Ok(public_key)
+    }
+
+    /// copy of the keys for a public key passed to add_rotation_keys()
+    pub fn get_rotation_keys(&self, public_key: &str) -> Result<Keys, HolochainError> {
+        self.rotation_keys
+            .lock()
+            .map_err(|_| HolochainError::new("the rotation keys lock is poisoned"))?
+            .get(public_key)
+            .cloned()
+            .ok_or_else(|| {
+                HolochainError::new(&format!("no rotation keys were added for {}", public_key))
+            })
+    }
+
+    /// forgets the keys for a public key once the agent has rotated to them
+    pub fn remove_rotation_keys(&self, public_key: &str) -> Result<(), HolochainError> {
+        self.rotation_keys
+            .lock()
+            .map_err(|_| HolochainError::new("the rotation keys lock is poisoned"))?
+            .remove(public_key);
+        Ok(())
+    }
 }
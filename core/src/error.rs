@@ -1,5 +1,6 @@
 use self::HolochainError::*;
-use std::{error::Error, fmt};
+use serde_json;
+use std::{error::Error, fmt, io};
 
 /// module for holding Holochain specific errors
 
@@ -14,6 +15,15 @@ pub enum HolochainError {
     ZomeNotFound(String),
     CapabilityNotFound(String),
     ZomeFunctionNotFound(String),
+    IoError(String),
+    SerializationError(String),
+    InvalidChain(String),
+    IdentityNotFound(String),
+    WrongPassphrase(String),
+    CorruptKeystore(String),
+    EntryTypeNotFound(String),
+    ValidationFailed(String),
+    MembraneRejected(String),
 }
 
 impl HolochainError {
@@ -51,10 +61,31 @@ impl Error for HolochainError {
             ZomeNotFound(err_msg) => &err_msg,
             CapabilityNotFound(err_msg) => &err_msg,
             ZomeFunctionNotFound(err_msg) => &err_msg,
+            IoError(err_msg) => &err_msg,
+            SerializationError(err_msg) => &err_msg,
+            InvalidChain(err_msg) => &err_msg,
+            IdentityNotFound(err_msg) => &err_msg,
+            WrongPassphrase(err_msg) => &err_msg,
+            CorruptKeystore(err_msg) => &err_msg,
+            EntryTypeNotFound(err_msg) => &err_msg,
+            ValidationFailed(err_msg) => &err_msg,
+            MembraneRejected(err_msg) => &err_msg,
         }
     }
 }
 
+impl From<io::Error> for HolochainError {
+    fn from(err: io::Error) -> Self {
+        HolochainError::IoError(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for HolochainError {
+    fn from(err: serde_json::Error) -> Self {
+        HolochainError::SerializationError(err.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +138,20 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    /// test that io and serialization errors convert to the matching variants
+    fn can_convert_errors() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "no file");
+        assert_eq!(
+            HolochainError::IoError("no file".to_string()),
+            HolochainError::from(io_err),
+        );
+
+        let serde_err = serde_json::from_str::<String>("not json").unwrap_err();
+        match HolochainError::from(serde_err) {
+            HolochainError::SerializationError(_) => (),
+            other => panic!("expected a SerializationError but got {:?}", other),
+        };
+    }
 }
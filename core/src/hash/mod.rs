@@ -1,8 +1,74 @@
 // use multihash::Multihash;
-use multihash::{encode, Hash};
-use rust_base58::ToBase58;
+use holochain_dna::HashAlgorithm;
+use multihash::{decode, encode, Hash};
+use rust_base58::{FromBase58, ToBase58};
 use serde::Serialize;
-use serde_json;
+use serde_json::{self, Map, Value};
+
+/// the version of the canonical serialization that is hashed
+/// part of every hashed string so that changes to the serialization can never produce hashes that
+/// collide with hashes made under a previous version
+pub const CANONICAL_VERSION: u64 = 1;
+
+/// the algorithm for hashes that aren't made under a DNA, same as the DNA default
+pub const DEFAULT_HASH: Hash = Hash::SHA2256;
+
+/// the multihash algorithm for a DNA hash algorithm
+pub fn multihash_algorithm(algorithm: HashAlgorithm) -> Hash {
+    match algorithm {
+        HashAlgorithm::Sha2256 => Hash::SHA2256,
+        HashAlgorithm::Sha2512 => Hash::SHA2512,
+        HashAlgorithm::Sha3256 => Hash::SHA3256,
+        HashAlgorithm::Sha3512 => Hash::SHA3512,
+        HashAlgorithm::Blake2b512 => Hash::Blake2b,
+    }
+}
+
+/// the algorithm a b58 hashed string was made with, None if it isn't a multihash
+pub fn algorithm_of(b58_hash: &str) -> Option<Hash> {
+    let bytes = b58_hash.from_base58().ok()?;
+    decode(&bytes).ok().map(|multihash| multihash.alg)
+}
+
+/// sorts the keys of every object in a JSON value
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<(String, Value)>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// the canonical serialization of a serializable something
+/// compact JSON with the keys of every object sorted, so the same data always serializes to the
+/// same string regardless of struct field order
+pub fn canonical_json<S: Serialize>(s: &S) -> String {
+    // @TODO error handling
+    // @see https://github.com/holochain/holochain-rust/issues/168
+    let value = serde_json::to_value(s).expect("should serialize without error");
+    sort_keys(value).to_string()
+}
+
+/// the one way headers, entries, metas, etc. are hashed
+/// the canonical JSON of the data is wrapped with the canonical version and the kind of data so
+/// that e.g. an entry and a header can never share a hash, even with identical serializations
+pub fn canonical_hash<S: Serialize>(kind: &str, s: &S, hash_type: Hash) -> String {
+    let value = serde_json::to_value(s).expect("should serialize without error");
+    let mut wrapped = Map::new();
+    wrapped.insert("version".into(), Value::from(CANONICAL_VERSION));
+    wrapped.insert("kind".into(), Value::from(kind));
+    wrapped.insert("content".into(), value);
+    str_to_b58_hash(&canonical_json(&Value::Object(wrapped)), hash_type)
+}
 
 /// convert bytes to a b58 hashed string
 pub fn bytes_to_b58_hash(bytes: &[u8], hash_type: Hash) -> String {
@@ -48,6 +114,83 @@ pub mod tests {
         );
     }
 
+    #[test]
+    /// canonical JSON sorts keys at every depth and has no whitespace
+    fn canonical_json_sorts_keys() {
+        #[derive(Serialize)]
+        struct Inner {
+            z: u8,
+            a: u8,
+        };
+        #[derive(Serialize)]
+        struct Outer {
+            b: Vec<Inner>,
+            a: &'static str,
+        };
+
+        assert_eq!(
+            r#"{"a":"x","b":[{"a":2,"z":1}]}"#,
+            canonical_json(&Outer {
+                b: vec![Inner { z: 1, a: 2 }],
+                a: "x",
+            }),
+        );
+    }
+
+    #[test]
+    /// canonical hashes depend on the kind, content and algorithm only
+    fn canonical_hash_known() {
+        #[derive(Serialize)]
+        struct Foo {
+            foo: u8,
+            bar: u8,
+        };
+        #[derive(Serialize)]
+        struct Bar {
+            bar: u8,
+            foo: u8,
+        };
+
+        let h = canonical_hash("foo", &Foo { foo: 5, bar: 6 }, Hash::SHA2256);
+        assert_eq!("Qmbn4EKt2t8Q3JfszmNToKQ9qX1ghVAgJdNQCg37HxFKpF", h);
+
+        // field order doesn't matter
+        assert_eq!(
+            h,
+            canonical_hash("foo", &Bar { bar: 6, foo: 5 }, Hash::SHA2256)
+        );
+        // the kind does
+        assert_ne!(
+            h,
+            canonical_hash("bar", &Foo { foo: 5, bar: 6 }, Hash::SHA2256)
+        );
+        // and so does the algorithm
+        assert_ne!(
+            h,
+            canonical_hash("foo", &Foo { foo: 5, bar: 6 }, Hash::SHA3256)
+        );
+    }
+
+    #[test]
+    /// every DNA hash algorithm hashes and can be read back from the hash
+    fn algorithms() {
+        for algorithm in vec![
+            HashAlgorithm::Sha2256,
+            HashAlgorithm::Sha2512,
+            HashAlgorithm::Sha3256,
+            HashAlgorithm::Sha3512,
+            HashAlgorithm::Blake2b512,
+        ] {
+            let hash_type = multihash_algorithm(algorithm);
+            let h = str_to_b58_hash("test data", hash_type);
+            assert_eq!(Some(hash_type), algorithm_of(&h));
+        }
+
+        assert_eq!(DEFAULT_HASH, multihash_algorithm(HashAlgorithm::default()));
+        assert_eq!(None, algorithm_of("not a hash"));
+        assert_eq!(None, algorithm_of(""));
+    }
+
     #[test]
     /// known hash for a serializable something
     fn can_serialize_to_b58_hash() {
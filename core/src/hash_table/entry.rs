@@ -1,4 +1,4 @@
-use hash;
+use hash::{self, DEFAULT_HASH};
 use multihash::Hash;
 use serde_json;
 use std::hash::{Hash as StdHash, Hasher};
@@ -44,15 +44,17 @@ impl Entry {
         }
     }
 
-    /// hashes the entry
+    /// hashes the entry with the default algorithm
+    /// entries pushed to a chain are hashed with the algorithm of the chain, @see hash_with()
     pub fn hash(&self) -> String {
-        // @TODO - this is the wrong string being hashed
-        // @see https://github.com/holochain/holochain-rust/issues/103
-        let string_to_hash = &self.content;
+        self.hash_with(DEFAULT_HASH)
+    }
 
-        // @TODO the hashing algo should not be hardcoded
-        // @see https://github.com/holochain/holochain-rust/issues/104
-        hash::str_to_b58_hash(string_to_hash, Hash::SHA2256)
+    /// hashes the entry with the given algorithm
+    pub fn hash_with(&self, hash_type: Hash) -> String {
+        // only the content is hashed, the entry type is covered by the header hash
+        // @see https://github.com/holochain/holochain-rust/issues/80
+        hash::canonical_hash("entry", &self.content, hash_type)
     }
 
     /// content getter
@@ -142,7 +144,7 @@ pub mod tests {
 
     /// the correct hash for test_entry()
     pub fn test_entry_hash() -> String {
-        "QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT".into()
+        "QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj".into()
     }
 
     /// dummy entry, same as test_entry()
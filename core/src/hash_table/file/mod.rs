@@ -0,0 +1,366 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rust_base58::FromBase58;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use error::HolochainError;
+use hash_table::{pair::Pair, pair_meta::PairMeta, HashTable, HashTableOp};
+
+/// the subdirectory of a FileTable that pairs are stored in
+const PAIRS_DIR: &str = "pairs";
+/// the subdirectory of a FileTable that pair metas are stored in
+const METAS_DIR: &str = "metas";
+
+/// HashTable that stores every Pair and PairMeta as a JSON file under a directory
+/// everything written survives the process, a new FileTable built against the same path sees all
+/// previously committed data
+/// <path>/pairs/<pair key>.json
+/// <path>/metas/<meta key>.json
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileTable {
+    path: PathBuf,
+}
+
+impl FileTable {
+    /// builds a FileTable against the given directory, creating it if needed
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<FileTable, HolochainError> {
+        let mut table = FileTable {
+            path: path.as_ref().to_path_buf(),
+        };
+        table.setup()?;
+        Ok(table)
+    }
+
+    /// getter for a copy of the table directory
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// the path of the file for a given key in the given subdirectory
+    /// keys can come from zome input so anything but a base58 hash is an error, otherwise a key
+    /// like "../x" would name a file outside the table
+    fn file_path(&self, dir: &str, key: &str) -> Result<PathBuf, HolochainError> {
+        if key.is_empty() || key.from_base58().is_err() {
+            return Err(HolochainError::new(&format!(
+                "hash table keys must be base58 hashes, not {:?}",
+                key
+            )));
+        }
+        Ok(self.path.join(dir).join(format!("{}.json", key)))
+    }
+
+    /// the file an op writes to
+    fn op_path(&self, op: &HashTableOp) -> Result<PathBuf, HolochainError> {
+        match op {
+            HashTableOp::Commit(pair) => self.file_path(PAIRS_DIR, &pair.key()),
+            HashTableOp::AssertMeta(meta) => self.file_path(METAS_DIR, &meta.key()),
+        }
+    }
+
+    /// serializes an op to a temporary file next to the file at path it will replace
+    /// the index of the op in its batch keeps temporary files unique within the batch
+    fn stage(
+        &self,
+        index: usize,
+        op: &HashTableOp,
+        path: &Path,
+    ) -> Result<PathBuf, HolochainError> {
+        let json = match op {
+            HashTableOp::Commit(pair) => serde_json::to_string(pair)?,
+            HashTableOp::AssertMeta(meta) => serde_json::to_string(meta)?,
+        };
+        let tmp_path = path.with_extension(format!("json.{}.tmp", index));
+        fs::write(&tmp_path, json)?;
+        Ok(tmp_path)
+    }
+
+    /// moves staged files over their targets in order
+    /// if any move fails every target that was already replaced is restored
+    fn publish(&self, staged: &[(PathBuf, PathBuf)]) -> Result<(), HolochainError> {
+        let mut published: Vec<(&PathBuf, Option<Vec<u8>>)> = Vec::new();
+        for (tmp_path, path) in staged {
+            let previous = if path.is_file() {
+                Some(fs::read(path)?)
+            } else {
+                None
+            };
+            if let Err(err) = fs::rename(tmp_path, path) {
+                for (path, previous) in published.into_iter().rev() {
+                    // @TODO what if the rollback itself fails?
+                    // @see https://github.com/holochain/holochain-rust/issues/142
+                    let _ = match previous {
+                        Some(contents) => fs::write(path, contents),
+                        None => fs::remove_file(path),
+                    };
+                }
+                return Err(err.into());
+            }
+            published.push((path, previous));
+        }
+        Ok(())
+    }
+
+    /// every PairMeta in the table
+    /// @TODO this is a slow way to do a lookup
+    /// @see https://github.com/holochain/holochain-rust/issues/50
+    fn metas(&self) -> Result<Vec<PairMeta>, HolochainError> {
+        let mut metas = Vec::new();
+        for dir_entry in fs::read_dir(self.path.join(METAS_DIR))? {
+            let path = dir_entry?.path();
+            // skip anything that isn't a complete meta file e.g. an interrupted write
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            metas.push(serde_json::from_str(&fs::read_to_string(&path)?)?);
+        }
+        Ok(metas)
+    }
+
+    /// deserializes data from a file, if it exists
+    fn read<T: DeserializeOwned>(&self, dir: &str, key: &str) -> Result<Option<T>, HolochainError> {
+        let path = self.file_path(dir, key)?;
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+}
+
+impl HashTable for FileTable {
+    fn setup(&mut self) -> Result<(), HolochainError> {
+        fs::create_dir_all(self.path.join(PAIRS_DIR))?;
+        fs::create_dir_all(self.path.join(METAS_DIR))?;
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> Result<(), HolochainError> {
+        // nothing is held open between calls and the data must outlive the table
+        Ok(())
+    }
+
+    fn apply(&mut self, ops: &[HashTableOp]) -> Result<(), HolochainError> {
+        for op in ops {
+            op.validate()?;
+        }
+
+        // write everything to temporary files first so that most failures (e.g. serialization,
+        // disk full) happen before any real file is touched
+        let mut staged = Vec::new();
+        for (index, op) in ops.iter().enumerate() {
+            match self
+                .op_path(op)
+                .and_then(|path| Ok((self.stage(index, op, &path)?, path)))
+            {
+                Ok(paths) => staged.push(paths),
+                Err(err) => {
+                    for (tmp_path, _) in staged {
+                        let _ = fs::remove_file(tmp_path);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        let result = self.publish(&staged);
+        if result.is_err() {
+            for (tmp_path, _) in staged {
+                let _ = fs::remove_file(tmp_path);
+            }
+        }
+        result
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Pair>, HolochainError> {
+        self.read(PAIRS_DIR, key)
+    }
+
+    fn get_meta(&self, key: &str) -> Result<Option<PairMeta>, HolochainError> {
+        self.read(METAS_DIR, key)
+    }
+
+    fn get_pair_meta(&self, pair: &Pair) -> Result<Vec<PairMeta>, HolochainError> {
+        let mut metas = self
+            .metas()?
+            .into_iter()
+            .filter(|meta| meta.pair() == pair.key())
+            .collect::<Vec<PairMeta>>();
+        // @TODO should this be sorted at all at this point?
+        // @see https://github.com/holochain/holochain-rust/issues/144
+        metas.sort();
+        Ok(metas)
+    }
+
+    fn last_txn(&self, source: &str) -> Result<u64, HolochainError> {
+        Ok(self
+            .metas()?
+            .into_iter()
+            .filter(|meta| meta.source() == source)
+            .map(|meta| meta.txn())
+            .max()
+            .unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    extern crate tempfile;
+
+    use self::tempfile::{tempdir, TempDir};
+    use agent::keys::tests::test_keys;
+    use error::HolochainError;
+    use hash_table::{
+        file::FileTable,
+        pair::tests::{test_pair, test_pair_a, test_pair_b},
+        pair_meta::tests::test_pair_meta,
+        test_suite, HashTable, HashTableOp,
+    };
+    use serde_json;
+    use std::fs;
+
+    /// builds a FileTable in a fresh temporary directory
+    /// the directory is deleted when the returned TempDir is dropped so keep it in scope
+    pub fn test_table() -> (FileTable, TempDir) {
+        let dir = tempdir().expect("should be able to create a temporary directory");
+        let table = FileTable::new(dir.path()).expect("should be able to build a FileTable");
+        (table, dir)
+    }
+
+    #[test]
+    /// smoke test
+    fn new() {
+        let (table, dir) = test_table();
+        assert_eq!(dir.path(), table.path());
+        assert!(dir.path().join("pairs").is_dir());
+        assert!(dir.path().join("metas").is_dir());
+    }
+
+    #[test]
+    /// tests for ht.setup()
+    fn setup() {
+        let (mut ht, _dir) = test_table();
+        assert_eq!(Ok(()), ht.setup());
+    }
+
+    #[test]
+    /// tests for ht.teardown()
+    fn teardown() {
+        let (mut ht, _dir) = test_table();
+        assert_eq!(Ok(()), ht.teardown());
+    }
+
+    #[test]
+    /// FileTable passes the HashTable conformance suite
+    fn conformance() {
+        let (mut ht, _dir) = test_table();
+        test_suite::run_all(&mut ht);
+    }
+
+    #[test]
+    /// everything committed to a FileTable is visible to a new FileTable at the same path
+    fn persistence() {
+        let dir = tempdir().expect("should be able to create a temporary directory");
+        let p1 = test_pair_a();
+        let p2 = test_pair_b();
+
+        {
+            let mut ht = FileTable::new(dir.path()).expect("should be able to build a FileTable");
+            ht.commit(&p1).expect("should be able to commit valid pair");
+            ht.modify(&test_keys(), &p1, &p2)
+                .expect("should be able to edit with valid pair");
+            ht.teardown().expect("teardown shouldn't fail");
+        }
+
+        let ht = FileTable::new(dir.path()).expect("should be able to build a FileTable");
+        assert_eq!(Ok(Some(p1.clone())), ht.get(&p1.key()));
+        assert_eq!(Ok(Some(p2.clone())), ht.get(&p2.key()));
+        assert_eq!(
+            2,
+            ht.get_pair_meta(&p1)
+                .expect("getting the metadata on a pair shouldn't fail")
+                .len()
+        );
+    }
+
+    #[test]
+    /// corrupt files surface as errors rather than missing data
+    fn corrupt_file() {
+        let (mut ht, dir) = test_table();
+        let p = test_pair();
+        ht.commit(&p).expect("should be able to commit valid pair");
+
+        fs::write(
+            dir.path().join("pairs").join(format!("{}.json", p.key())),
+            "not json",
+        ).expect("should be able to overwrite a pair file");
+
+        match ht.get(&p.key()) {
+            Err(HolochainError::SerializationError(_)) => (),
+            other => panic!("expected a serialization error but got {:?}", other),
+        }
+    }
+
+    #[test]
+    /// a batch that fails part way through writing files leaves the table as it was
+    fn apply_io_failure() {
+        let (mut ht, dir) = test_table();
+        let p1 = test_pair_a();
+        let p2 = test_pair_b();
+        let m = test_pair_meta();
+
+        ht.commit(&p1).expect("should be able to commit valid pair");
+
+        // a directory where the meta file should go makes the last write of the batch fail
+        let blocker = dir.path().join("metas").join(format!("{}.json", m.key()));
+        fs::create_dir(&blocker).expect("should be able to block the meta file");
+
+        assert!(
+            ht.apply(&[
+                HashTableOp::Commit(p1.clone()),
+                HashTableOp::Commit(p2.clone()),
+                HashTableOp::AssertMeta(m.clone()),
+            ]).is_err()
+        );
+
+        fs::remove_dir(&blocker).expect("should be able to unblock the meta file");
+
+        // the pair that already existed is still there, the new pair was rolled back
+        assert_eq!(Ok(Some(p1.clone())), ht.get(&p1.key()));
+        assert_eq!(Ok(None), ht.get(&p2.key()));
+        assert_eq!(Ok(None), ht.get_meta(&m.key()));
+
+        // no temporary files are left behind
+        for sub_dir in vec!["pairs", "metas"] {
+            for dir_entry in fs::read_dir(dir.path().join(sub_dir)).unwrap() {
+                let path = dir_entry.unwrap().path();
+                assert!(
+                    !path.to_string_lossy().ends_with(".tmp"),
+                    "temporary file left behind: {:?}",
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    /// keys that aren't hashes can't reach files outside the table
+    fn path_traversal() {
+        let (ht, dir) = test_table();
+        let p = test_pair();
+
+        // a pair file where a traversal key would find it
+        fs::write(
+            dir.path().join("pairs").join("outside.json"),
+            serde_json::to_string(&p).unwrap(),
+        ).expect("should be able to write a pair file");
+
+        for key in vec!["../pairs/outside", "..", "a/b", "a\\b", ""] {
+            assert!(ht.get(key).is_err(), "{:?}", key);
+            assert!(ht.get_meta(key).is_err(), "{:?}", key);
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use agent::keys::{Key, Keys};
 use chain::Chain;
-use hash;
+use chrono::{DateTime, Utc};
+use error::HolochainError;
+use hash::{self, DEFAULT_HASH};
 use hash_table::{entry::Entry, HashTable};
 use multihash::Hash;
 
@@ -19,6 +22,8 @@ pub struct Header {
     entry: String,
     /// link to the most recent header of the same type, None is valid only for the first of type
     type_next: Option<String>,
+    /// base58 public key of the agent that signed the header, empty if unsigned
+    source: String,
     /// agent's cryptographic signature
     signature: String,
 }
@@ -42,19 +47,17 @@ impl Header {
     pub fn new<T: HashTable>(chain: &Chain<T>, entry: &Entry) -> Header {
         Header {
             entry_type: entry.entry_type().clone(),
-            // @TODO implement timestamps
-            // https://github.com/holochain/holochain-rust/issues/70
-            time: String::new(),
-            next: chain.top().as_ref().map(|p| p.header().hash()),
-            entry: entry.hash().to_string(),
+            time: chain.clock().now().to_rfc3339(),
+            next: chain.top().map(|p| p.header().hash()),
+            entry: entry.hash_with(chain.hash_algorithm()),
             type_next: chain
                 .top_type(&entry.entry_type())
                 // @TODO inappropriate expect()?
                 // @see https://github.com/holochain/holochain-rust/issues/147
                 .expect("top type should never error")
                 .map(|p| p.header().hash()),
-            // @TODO implement signatures
-            // https://github.com/holochain/holochain-rust/issues/71
+            // chains owned by an agent sign headers on push, @see Header::sign()
+            source: String::new(),
             signature: String::new(),
         }
     }
@@ -69,6 +72,18 @@ impl Header {
         self.time.clone()
     }
 
+    /// parses the time into a timestamp
+    pub fn timestamp(&self) -> Result<DateTime<Utc>, HolochainError> {
+        DateTime::parse_from_rfc3339(&self.time)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|err| {
+                HolochainError::new(&format!(
+                    "header time {:?} is not an ISO8601 timestamp: {}",
+                    self.time, err
+                ))
+            })
+    }
+
     /// next getter
     pub fn next(&self) -> Option<String> {
         self.next.clone()
@@ -84,34 +99,65 @@ impl Header {
         self.type_next.clone()
     }
 
+    /// source getter
+    pub fn source(&self) -> String {
+        self.source.clone()
+    }
+
     /// signature getter
     pub fn signature(&self) -> String {
         self.signature.clone()
     }
 
+    /// returns a copy of the header signed by the given keys, with the keys as the source
+    /// the signature covers everything else in the header so the signed header has a new hash
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys can't sign, @see Keys::sign()
+    pub fn sign(&self, keys: &Keys) -> Header {
+        let mut signed = Header {
+            source: keys.public_key().to_b58(),
+            ..self.clone()
+        };
+        signed.signature = keys.sign(signed.signed_content().as_bytes());
+        signed
+    }
+
+    /// true if the header has a source or signature
+    pub fn is_signed(&self) -> bool {
+        !self.source.is_empty() || !self.signature.is_empty()
+    }
+
+    /// true if the signature is a valid signature of this header by the source
+    pub fn verify_signature(&self) -> bool {
+        Key::from_b58(&self.source)
+            .map(|key| key.verify(self.signed_content().as_bytes(), &self.signature))
+            .unwrap_or(false)
+    }
+
+    /// everything covered by the signature, i.e. the canonical JSON of everything but the signature
+    fn signed_content(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.signature = String::new();
+        hash::canonical_json(&unsigned)
+    }
+
+    /// the algorithm this header and its entry are hashed with
+    /// that is the algorithm of the chain the header was built for, as recorded in the entry hash
+    pub fn hash_algorithm(&self) -> Hash {
+        hash::algorithm_of(&self.entry).unwrap_or(DEFAULT_HASH)
+    }
+
     /// hashes the header
     pub fn hash(&self) -> String {
-        // @TODO this is the wrong string being hashed
-        // @see https://github.com/holochain/holochain-rust/issues/103
-        let pieces: [&str; 6] = [
-            &self.entry_type,
-            &self.time,
-            &self.next.clone().unwrap_or_default(),
-            &self.entry,
-            &self.type_next.clone().unwrap_or_default(),
-            &self.signature,
-        ];
-        let string_to_hash = pieces.concat();
-
-        // @TODO the hashing algo should not be hardcoded
-        // @see https://github.com/holochain/holochain-rust/issues/104
-        hash::str_to_b58_hash(&string_to_hash, Hash::SHA2256)
+        hash::canonical_hash("header", &self, self.hash_algorithm())
     }
 
     /// returns true if the header is valid
     pub fn validate(&self) -> bool {
-        // always valid iff immutable and new() enforces validity
-        true
+        // new() enforces validity and headers are immutable, except that sign() adds a signature
+        !self.is_signed() || self.verify_signature()
     }
 
     /// returns the key for use in hash table lookups, e.g. chain.get()
@@ -122,7 +168,9 @@ impl Header {
 
 #[cfg(test)]
 mod tests {
+    use agent::keys::tests::{test_keys, test_keys_b};
     use chain::tests::test_chain;
+    use clock::tests::test_time;
     use hash_table::{entry::Entry, header::Header, pair::tests::test_pair};
 
     /// returns a dummy header for use in tests
@@ -200,7 +248,21 @@ mod tests {
         let e = Entry::new(t, "");
         let h = Header::new(&chain, &e);
 
-        assert_eq!(h.time(), "");
+        assert_eq!(h.time(), test_time().to_rfc3339());
+    }
+
+    #[test]
+    /// tests for header.timestamp()
+    fn timestamp() {
+        let chain = test_chain();
+        let e = Entry::new("foo", "");
+        let h = Header::new(&chain, &e);
+
+        assert_eq!(Ok(test_time()), h.timestamp());
+
+        let mut bad = h.clone();
+        bad.time = String::new();
+        assert!(bad.timestamp().is_err());
     }
 
     #[test]
@@ -285,9 +347,45 @@ mod tests {
         let e = Entry::new(t, "");
         let h = Header::new(&chain, &e);
 
+        assert_eq!("", h.source());
         assert_eq!("", h.signature());
     }
 
+    #[test]
+    /// tests for header.sign(), header.verify_signature() and header.validate()
+    fn sign() {
+        let chain = test_chain();
+        let h = Header::new(&chain, &Entry::new("foo", ""));
+        assert!(!h.is_signed());
+        assert!(!h.verify_signature());
+        assert!(h.validate());
+
+        let signed = h.sign(&test_keys());
+        assert!(signed.is_signed());
+        assert_eq!(test_keys().public_key().to_b58(), signed.source());
+        assert_ne!("", signed.signature());
+        assert_ne!(h.hash(), signed.hash());
+        assert!(signed.verify_signature());
+        assert!(signed.validate());
+
+        // tampering with any field invalidates the signature
+        let mut tampered = signed.clone();
+        tampered.entry_type = "bar".into();
+        assert!(!tampered.verify_signature());
+        assert!(!tampered.validate());
+
+        // claiming another source invalidates the signature
+        let mut tampered = signed.clone();
+        tampered.source = test_keys_b().public_key().to_b58();
+        assert!(!tampered.verify_signature());
+        assert!(!tampered.validate());
+
+        // a signature without a source is never valid
+        let mut tampered = signed.clone();
+        tampered.source = String::new();
+        assert!(!tampered.validate());
+    }
+
     #[test]
     /// test header.hash() against a known value
     fn hash_known() {
@@ -298,7 +396,7 @@ mod tests {
         let e = Entry::new(t, "");
         let h = Header::new(&chain, &e);
 
-        assert_eq!("QmSpmouzp7PoTFeEcrG1GWVGVneacJcuwU91wkDCGYvPZ9", h.hash());
+        assert_eq!("QmTDbDxbSBiukK6ynr4PKf7Qq1ADpcEbpuktEFMDnwoZWR", h.hash());
     }
 
     #[test]
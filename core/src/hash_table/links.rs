@@ -0,0 +1,101 @@
+use serde_json;
+
+/// links are stored as PairMeta on the base pair, the attribute is the tag with this prefix and
+/// the value is the target entry hash
+/// @see HashTable::add_link()
+pub const LINK_PREFIX: &str = "link:";
+/// removed links are stored as PairMeta on the base pair as per LINK_PREFIX, with this prefix
+/// @see HashTable::remove_link()
+pub const LINK_TOMBSTONE_PREFIX: &str = "link-tombstone:";
+
+/// the meta attribute of a link with the given tag
+pub fn link_attribute(tag: &str) -> String {
+    format!("{}{}", LINK_PREFIX, tag)
+}
+
+/// the meta attribute of a removed link with the given tag
+pub fn link_tombstone_attribute(tag: &str) -> String {
+    format!("{}{}", LINK_TOMBSTONE_PREFIX, tag)
+}
+
+/// a tagged link from a base entry to a target entry, both referenced by entry hash
+/// the entry type of the base must declare the tag and the target entry type in its links_to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Link {
+    pub base: String,
+    pub target: String,
+    pub tag: String,
+}
+
+impl Link {
+    pub fn new(base: &str, target: &str, tag: &str) -> Link {
+        Link {
+            base: base.into(),
+            target: target.into(),
+            tag: tag.into(),
+        }
+    }
+
+    /// the JSON passed to the links_to validation WASM
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("link should serialize")
+    }
+}
+
+/// every link with a tag from a base entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkQuery {
+    pub base: String,
+    pub tag: String,
+}
+
+impl LinkQuery {
+    pub fn new(base: &str, tag: &str) -> LinkQuery {
+        LinkQuery {
+            base: base.into(),
+            tag: tag.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{link_attribute, link_tombstone_attribute, Link, LinkQuery};
+    use hash_table::entry::tests::{test_entry_a, test_entry_b};
+
+    /// dummy link tag
+    pub fn test_tag() -> String {
+        "test-tag".into()
+    }
+
+    /// dummy link from test_entry_a() to test_entry_b()
+    pub fn test_link() -> Link {
+        Link::new(&test_entry_a().hash(), &test_entry_b().hash(), &test_tag())
+    }
+
+    /// dummy query for test_link()
+    pub fn test_link_query() -> LinkQuery {
+        LinkQuery::new(&test_entry_a().hash(), &test_tag())
+    }
+
+    #[test]
+    /// test that links and removed links have distinct attributes per tag
+    fn attributes() {
+        assert_eq!("link:test-tag", link_attribute(&test_tag()));
+        assert_eq!(
+            "link-tombstone:test-tag",
+            link_tombstone_attribute(&test_tag())
+        );
+        assert_ne!(link_attribute("a"), link_attribute("b"));
+    }
+
+    #[test]
+    /// test link.to_json()
+    fn to_json() {
+        let link = Link::new("base", "target", "tag");
+        assert_eq!(
+            r#"{"base":"base","target":"target","tag":"tag"}"#,
+            link.to_json(),
+        );
+    }
+}
@@ -2,13 +2,7 @@ use std::collections::HashMap;
 
 use error::HolochainError;
 
-use agent::keys::Keys;
-use hash_table::{
-    pair::Pair,
-    pair_meta::PairMeta,
-    status::{CRUDStatus, LINK_NAME, STATUS_NAME},
-    HashTable,
-};
+use hash_table::{pair::Pair, pair_meta::PairMeta, HashTable, HashTableOp};
 
 #[derive(Serialize, Debug, Clone, PartialEq, Default)]
 pub struct MemTable {
@@ -34,8 +28,21 @@ impl HashTable for MemTable {
         Ok(())
     }
 
-    fn commit(&mut self, pair: &Pair) -> Result<(), HolochainError> {
-        self.pairs.insert(pair.key(), pair.clone());
+    fn apply(&mut self, ops: &[HashTableOp]) -> Result<(), HolochainError> {
+        // inserts can't fail so validating everything up front is enough for atomicity
+        for op in ops {
+            op.validate()?;
+        }
+        for op in ops {
+            match op {
+                HashTableOp::Commit(pair) => {
+                    self.pairs.insert(pair.key(), pair.clone());
+                }
+                HashTableOp::AssertMeta(meta) => {
+                    self.meta.insert(meta.key(), meta.clone());
+                }
+            }
+        }
         Ok(())
     }
 
@@ -43,47 +50,11 @@ impl HashTable for MemTable {
         Ok(self.pairs.get(key).cloned())
     }
 
-    fn modify(
-        &mut self,
-        keys: &Keys,
-        old_pair: &Pair,
-        new_pair: &Pair,
-    ) -> Result<(), HolochainError> {
-        self.commit(new_pair)?;
-
-        // @TODO what if meta fails when commit succeeds?
-        // @see https://github.com/holochain/holochain-rust/issues/142
-        self.assert_meta(PairMeta::new(
-            keys,
-            &old_pair,
-            STATUS_NAME,
-            &CRUDStatus::MODIFIED.bits().to_string(),
-        ))?;
-
-        // @TODO what if meta fails when commit succeeds?
-        // @see https://github.com/holochain/holochain-rust/issues/142
-        self.assert_meta(PairMeta::new(keys, &old_pair, LINK_NAME, &new_pair.key()))
-    }
-
-    fn retract(&mut self, keys: &Keys, pair: &Pair) -> Result<(), HolochainError> {
-        self.assert_meta(PairMeta::new(
-            keys,
-            &pair,
-            STATUS_NAME,
-            &CRUDStatus::DELETED.bits().to_string(),
-        ))
-    }
-
-    fn assert_meta(&mut self, meta: PairMeta) -> Result<(), HolochainError> {
-        self.meta.insert(meta.key(), meta);
-        Ok(())
-    }
-
-    fn get_meta(&mut self, key: &str) -> Result<Option<PairMeta>, HolochainError> {
+    fn get_meta(&self, key: &str) -> Result<Option<PairMeta>, HolochainError> {
         Ok(self.meta.get(key).cloned())
     }
 
-    fn get_pair_meta(&mut self, pair: &Pair) -> Result<Vec<PairMeta>, HolochainError> {
+    fn get_pair_meta(&self, pair: &Pair) -> Result<Vec<PairMeta>, HolochainError> {
         let mut metas = self
             .meta
             .values()
@@ -95,6 +66,16 @@ impl HashTable for MemTable {
         metas.sort();
         Ok(metas)
     }
+
+    fn last_txn(&self, source: &str) -> Result<u64, HolochainError> {
+        Ok(self
+            .meta
+            .values()
+            .filter(|m| m.source() == source)
+            .map(|m| m.txn())
+            .max()
+            .unwrap_or(0))
+    }
 }
 
 #[cfg(test)]
@@ -109,13 +90,19 @@ pub mod tests {
             PairMeta,
         },
         status::{CRUDStatus, LINK_NAME, STATUS_NAME},
-        HashTable,
+        test_suite, HashTable,
     };
 
     pub fn test_table() -> MemTable {
         MemTable::new()
     }
 
+    #[test]
+    /// MemTable passes the HashTable conformance suite
+    fn conformance() {
+        test_suite::run_all(&mut test_table());
+    }
+
     #[test]
     /// smoke test
     fn new() {
@@ -158,16 +145,17 @@ pub mod tests {
 
         assert_eq!(
             vec![
-                PairMeta::new(&test_keys(), &p1, LINK_NAME, &p2.key()),
-                PairMeta::new(
-                    &test_keys(),
-                    &p1,
-                    STATUS_NAME,
-                    &CRUDStatus::MODIFIED.bits().to_string(),
+                (LINK_NAME.to_string(), p2.key()),
+                (
+                    STATUS_NAME.to_string(),
+                    CRUDStatus::MODIFIED.bits().to_string(),
                 ),
             ],
             ht.get_pair_meta(&p1)
                 .expect("getting the metadata on a pair shouldn't fail")
+                .iter()
+                .map(|m| (m.attribute(), m.value()))
+                .collect::<Vec<(String, String)>>()
         );
 
         let empty_vec: Vec<PairMeta> = Vec::new();
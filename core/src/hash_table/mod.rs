@@ -1,41 +1,269 @@
 pub mod entry;
+pub mod file;
 pub mod header;
+pub mod links;
 pub mod memory;
 pub mod pair;
 pub mod pair_meta;
 pub mod status;
+#[cfg(any(test, feature = "test-suite"))]
+pub mod test_suite;
 
 use agent::keys::Keys;
 use error::HolochainError;
-use hash_table::{pair::Pair, pair_meta::PairMeta};
+use hash_table::{
+    links::{link_attribute, link_tombstone_attribute},
+    pair::Pair,
+    pair_meta::PairMeta,
+    status::{CRUDStatus, LINK_NAME, STATUS_NAME},
+};
+use std::collections::{HashMap, HashSet};
 
-pub trait HashTable {
+/// a single write to a HashTable
+/// writes are always applied through HashTable::apply() so that several of them can be applied
+/// as one transaction
+#[derive(Clone, Debug, PartialEq)]
+pub enum HashTableOp {
+    Commit(Pair),
+    AssertMeta(PairMeta),
+}
+
+impl HashTableOp {
+    /// returns an error if the op must not be applied to any HashTable
+    pub fn validate(&self) -> Result<(), HolochainError> {
+        match self {
+            HashTableOp::Commit(pair) => if pair.validate() {
+                Ok(())
+            } else {
+                Err(HolochainError::new(&format!(
+                    "attempted to commit an invalid pair: {}",
+                    pair.key()
+                )))
+            },
+            HashTableOp::AssertMeta(meta) => if meta.verify() {
+                Ok(())
+            } else {
+                Err(HolochainError::new(&format!(
+                    "attempted to assert meta with an invalid signature: {}",
+                    meta.key()
+                )))
+            },
+        }
+    }
+}
+
+/// HashTable implementations are shared between threads behind an RwLock, so reads take &self
+pub trait HashTable: Send + Sync {
     // internal state management
     fn setup(&mut self) -> Result<(), HolochainError>;
     fn teardown(&mut self) -> Result<(), HolochainError>;
 
     // crud
+    /// apply a batch of writes as a single transaction, either every op is applied or none are
+    /// implementations must check every op with HashTableOp::validate() before writing anything
+    fn apply(&mut self, ops: &[HashTableOp]) -> Result<(), HolochainError>;
     /// add a Pair to the HashTable, analogous to chain.push() but ordering is not enforced
-    fn commit(&mut self, pair: &Pair) -> Result<(), HolochainError>;
+    fn commit(&mut self, pair: &Pair) -> Result<(), HolochainError> {
+        self.apply(&[HashTableOp::Commit(pair.clone())])
+    }
     /// lookup a Pair from the HashTable by Pair/Header key
     fn get(&self, key: &str) -> Result<Option<Pair>, HolochainError>;
     /// add a new Pair to the HashTable as per commit and status link an old Pair as MODIFIED
+    /// the new pair and both metas are applied as a single transaction
     fn modify(
         &mut self,
         keys: &Keys,
         old_pair: &Pair,
         new_pair: &Pair,
-    ) -> Result<(), HolochainError>;
+    ) -> Result<(), HolochainError> {
+        self.apply(&[
+            HashTableOp::Commit(new_pair.clone()),
+            HashTableOp::AssertMeta(PairMeta::new(
+                keys,
+                &old_pair,
+                STATUS_NAME,
+                &CRUDStatus::MODIFIED.bits().to_string(),
+            )),
+            HashTableOp::AssertMeta(PairMeta::new(keys, &old_pair, LINK_NAME, &new_pair.key())),
+        ])
+    }
     /// set the status of a Pair to DELETED
-    fn retract(&mut self, keys: &Keys, pair: &Pair) -> Result<(), HolochainError>;
+    fn retract(&mut self, keys: &Keys, pair: &Pair) -> Result<(), HolochainError> {
+        self.assert_meta(PairMeta::new(
+            keys,
+            &pair,
+            STATUS_NAME,
+            &CRUDStatus::DELETED.bits().to_string(),
+        ))
+    }
+
+    /// add a Pair that failed validation to the HashTable with its status set to REJECTED
+    /// the pair and its status meta are applied as a single transaction
+    fn reject(&mut self, keys: &Keys, pair: &Pair) -> Result<(), HolochainError> {
+        self.apply(&[
+            HashTableOp::Commit(pair.clone()),
+            HashTableOp::AssertMeta(PairMeta::new(
+                keys,
+                pair,
+                STATUS_NAME,
+                &CRUDStatus::REJECTED.bits().to_string(),
+            )),
+        ])
+    }
 
     // meta
     /// assert a given PairMeta in the HashTable
-    fn assert_meta(&mut self, meta: PairMeta) -> Result<(), HolochainError>;
+    fn assert_meta(&mut self, meta: PairMeta) -> Result<(), HolochainError> {
+        self.apply(&[HashTableOp::AssertMeta(meta)])
+    }
     /// lookup a PairMeta from the HashTable by key
-    fn get_meta(&mut self, key: &str) -> Result<Option<PairMeta>, HolochainError>;
+    fn get_meta(&self, key: &str) -> Result<Option<PairMeta>, HolochainError>;
     /// lookup all PairMeta for a given Pair
-    fn get_pair_meta(&mut self, pair: &Pair) -> Result<Vec<PairMeta>, HolochainError>;
+    fn get_pair_meta(&self, pair: &Pair) -> Result<Vec<PairMeta>, HolochainError>;
+    /// the highest txn of any PairMeta asserted by the source, 0 if it never asserted anything
+    /// the logical clock of the source's keys must carry on from here, @see Keys::observe_txn()
+    fn last_txn(&self, source: &str) -> Result<u64, HolochainError>;
+
+    /// lookup the most recently asserted PairMeta with the given attribute for a Pair
+    /// "most recent" is by txn as per PairMeta ordering
+    fn get_latest_meta(
+        &self,
+        pair: &Pair,
+        attribute: &str,
+    ) -> Result<Option<PairMeta>, HolochainError> {
+        Ok(self
+            .get_pair_meta(pair)?
+            .into_iter()
+            .filter(|m| m.attribute() == attribute)
+            .max())
+    }
+
+    // status
+    /// the CRUDStatus of a Pair as per its latest status meta, LIVE if no status has been asserted
+    fn get_status(&self, pair: &Pair) -> Result<CRUDStatus, HolochainError> {
+        match self.get_latest_meta(pair, STATUS_NAME)? {
+            Some(meta) => meta
+                .value()
+                .parse::<u8>()
+                .ok()
+                .and_then(CRUDStatus::from_bits)
+                .ok_or_else(|| {
+                    HolochainError::new(&format!(
+                        "invalid {} meta for pair {}: {}",
+                        STATUS_NAME,
+                        pair.key(),
+                        meta.value(),
+                    ))
+                }),
+            None => Ok(CRUDStatus::LIVE),
+        }
+    }
+
+    /// lookup a Pair by key, only if its CRUDStatus matches the mask
+    /// e.g. CRUDStatus::LIVE returns nothing for DELETED, REJECTED or MODIFIED pairs
+    fn get_with_status(
+        &self,
+        key: &str,
+        mask: CRUDStatus,
+    ) -> Result<Option<Pair>, HolochainError> {
+        match self.get(key)? {
+            Some(pair) => Ok(if mask.intersects(self.get_status(&pair)?) {
+                Some(pair)
+            } else {
+                None
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// lookup the latest version of a Pair by following crud-link meta from MODIFIED pairs
+    /// the latest version is only returned if its CRUDStatus matches the mask
+    /// e.g. CRUDStatus::LIVE returns nothing if the latest version was DELETED or REJECTED
+    fn get_latest(&self, key: &str, mask: CRUDStatus) -> Result<Option<Pair>, HolochainError> {
+        let mut seen = HashSet::new();
+        let mut key = key.to_string();
+        loop {
+            if !seen.insert(key.clone()) {
+                return Err(HolochainError::new(&format!(
+                    "{} meta loops back to pair {}",
+                    LINK_NAME, key,
+                )));
+            }
+
+            let pair = match self.get(&key)? {
+                Some(pair) => pair,
+                None => return Ok(None),
+            };
+            let status = self.get_status(&pair)?;
+
+            let link = self.get_latest_meta(&pair, LINK_NAME)?.map(|m| m.value());
+
+            match link {
+                Some(ref next) if status.contains(CRUDStatus::MODIFIED) => key = next.clone(),
+                _ => {
+                    return Ok(if mask.intersects(status) {
+                        Some(pair)
+                    } else {
+                        None
+                    })
+                }
+            }
+        }
+    }
+
+    // links
+    /// link a Pair to a target entry hash with a tag by asserting link meta on the Pair
+    fn add_link(
+        &mut self,
+        keys: &Keys,
+        base: &Pair,
+        tag: &str,
+        target: &str,
+    ) -> Result<(), HolochainError> {
+        self.assert_meta(PairMeta::new(keys, base, &link_attribute(tag), target))
+    }
+    /// remove a link by asserting a tombstone for it, nothing is ever deleted from the HashTable
+    /// a link added again after its tombstone is live again
+    fn remove_link(
+        &mut self,
+        keys: &Keys,
+        base: &Pair,
+        tag: &str,
+        target: &str,
+    ) -> Result<(), HolochainError> {
+        self.assert_meta(PairMeta::new(
+            keys,
+            base,
+            &link_tombstone_attribute(tag),
+            target,
+        ))
+    }
+    /// the target entry hashes of every live link from a Pair with the given tag, oldest first
+    /// a link is live if its latest link or tombstone meta (by txn) is a link
+    fn get_links(&self, base: &Pair, tag: &str) -> Result<Vec<String>, HolochainError> {
+        let link = link_attribute(tag);
+        let tombstone = link_tombstone_attribute(tag);
+
+        let mut latest: HashMap<String, PairMeta> = HashMap::new();
+        for meta in self.get_pair_meta(base)? {
+            if meta.attribute() == link || meta.attribute() == tombstone {
+                let is_latest = match latest.get(&meta.value()) {
+                    Some(other) => meta.txn() > other.txn(),
+                    None => true,
+                };
+                if is_latest {
+                    latest.insert(meta.value(), meta);
+                }
+            }
+        }
+
+        let mut links: Vec<&PairMeta> = latest
+            .values()
+            .filter(|meta| meta.attribute() == link)
+            .collect();
+        links.sort_by_key(|meta| meta.txn());
+        Ok(links.into_iter().map(|meta| meta.value()).collect())
+    }
 
     // query
     // @TODO how should we handle queries?
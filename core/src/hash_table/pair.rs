@@ -1,4 +1,6 @@
+use agent::{encryption::decrypt_entry, keys::Keys};
 use chain::Chain;
+use error::HolochainError;
 use hash_table::{entry::Entry, header::Header, HashTable};
 use serde_json;
 
@@ -43,6 +45,26 @@ impl Pair {
         p
     }
 
+    /// returns a copy of the pair with the header signed by the given keys
+    /// the signed pair has a new key, @see Header::sign()
+    pub fn sign(&self, keys: &Keys) -> Pair {
+        Pair {
+            header: self.header.sign(keys),
+            entry: self.entry.clone(),
+        }
+    }
+
+    /// returns a copy of the pair with its encrypted entry decrypted by the keys that encrypted it
+    /// the header still hashes the encrypted entry so the decrypted pair does NOT validate, it is
+    /// only for the owner to read
+    /// @see agent::encryption::encrypt_entry()
+    pub fn decrypt(&self, keys: &Keys) -> Result<Pair, HolochainError> {
+        Ok(Pair {
+            header: self.header.clone(),
+            entry: decrypt_entry(keys, &self.entry)?,
+        })
+    }
+
     /// header getter
     pub fn header(&self) -> &Header {
         &self.header
@@ -63,7 +85,7 @@ impl Pair {
         // the header and entry must validate independently
         self.header.validate() && self.entry.validate()
         // the header entry hash must be the same as the entry hash
-        && self.header.entry() == self.entry.hash()
+        && self.header.entry() == self.entry.hash_with(self.header.hash_algorithm())
         // the entry_type must line up across header and entry
         && self.header.entry_type() == self.entry.entry_type()
     }
@@ -79,21 +101,29 @@ impl Pair {
     }
 
     /// deserialize a Pair from a canonical JSON string
-    ///
-    /// # Panics
-    ///
-    /// Panics if the string given isn't valid JSON.
+    /// errors if the string given isn't valid JSON or the header has an invalid signature, e.g.
+    /// the JSON was read from a corrupt file or forged by a peer
     /// @TODO accept canonical JSON
     /// @see https://github.com/holochain/holochain-rust/issues/75
-    pub fn from_json(s: &str) -> Pair {
-        let pair: Pair = serde_json::from_str(s).expect("json should be valid");
-        pair
+    pub fn from_json(s: &str) -> Result<Pair, HolochainError> {
+        let pair: Pair = serde_json::from_str(s)?;
+        if pair.header.is_signed() && !pair.header.verify_signature() {
+            return Err(HolochainError::new(&format!(
+                "pair {} has an invalid signature",
+                pair.key()
+            )));
+        }
+        Ok(pair)
     }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::Pair;
+    use agent::{
+        encryption::encrypt_entry,
+        keys::tests::{test_keys, test_keys_b},
+    };
     use chain::tests::test_chain;
     use hash_table::{
         entry::{
@@ -134,6 +164,24 @@ pub mod tests {
         assert_eq!(&h1, p1.header());
     }
 
+    #[test]
+    /// tests for pair.decrypt()
+    fn decrypt() {
+        let encrypted = encrypt_entry(&test_keys(), &test_entry()).unwrap();
+        let p = Pair::new(&test_chain(), encrypted);
+
+        let decrypted = p
+            .decrypt(&test_keys())
+            .expect("the owner should decrypt the pair");
+        assert_eq!(p.header(), decrypted.header());
+        assert_eq!(&test_entry(), decrypted.entry());
+        // the header hashes the encrypted entry
+        assert!(!decrypted.validate());
+
+        assert!(p.decrypt(&test_keys_b()).is_err());
+        assert!(test_pair().decrypt(&test_keys()).is_err());
+    }
+
     #[test]
     /// tests for pair.header()
     fn header() {
@@ -175,12 +223,36 @@ pub mod tests {
     #[test]
     /// test JSON roundtrip for pairs
     fn json_roundtrip() {
-        let json = r#"{"header":{"entry_type":"testEntryType","time":"","next":null,"entry":"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT","type_next":null,"signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}}"#;
+        let json = r#"{"header":{"entry_type":"testEntryType","time":"2018-08-01T00:00:00+00:00","next":null,"entry":"QmTM2KuWfghzZtoQR9AGTDrwGkNJgVP7ktDnnTbuWQAjLj","type_next":null,"source":"","signature":""},"entry":{"content":"test entry content","entry_type":"testEntryType"}}"#;
 
         assert_eq!(json, test_pair().to_json());
 
-        assert_eq!(test_pair(), Pair::from_json(&json));
+        assert_eq!(Ok(test_pair()), Pair::from_json(&json));
+
+        assert_eq!(Ok(test_pair()), Pair::from_json(&test_pair().to_json()));
+
+        assert!(Pair::from_json("not json").is_err());
+    }
 
-        assert_eq!(test_pair(), Pair::from_json(&test_pair().to_json()));
+    #[test]
+    /// test that signed pairs round trip through JSON
+    fn json_roundtrip_signed() {
+        let signed = test_pair().sign(&test_keys());
+        assert!(signed.validate());
+        assert_eq!(Ok(signed.clone()), Pair::from_json(&signed.to_json()));
+    }
+
+    #[test]
+    /// test that from_json() rejects pairs with invalid signatures
+    fn from_json_bad_signature() {
+        let signed = test_pair().sign(&test_keys());
+        let forged = Pair::from_json(&signed.to_json().replace(
+            &test_keys().public_key().to_b58(),
+            &test_keys_b().public_key().to_b58(),
+        ));
+        match forged {
+            Err(err) => assert!(err.to_string().contains("has an invalid signature")),
+            Ok(pair) => panic!("accepted a forged pair: {:?}", pair),
+        }
     }
 }
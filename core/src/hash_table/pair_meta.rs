@@ -1,10 +1,9 @@
-use agent::keys::Keys;
-use hash::serializable_to_b58_hash;
+use agent::keys::{Key, Keys};
+use hash::{algorithm_of, canonical_hash, canonical_json, DEFAULT_HASH};
 use hash_table::pair::Pair;
-use multihash::Hash;
 use std::cmp::Ordering;
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 /// PairMeta represents an extended form of EAV (entity-attribute-value) data
 /// E = the pair key for hash table lookups
 /// A = the name of the meta attribute
@@ -12,33 +11,27 @@ use std::cmp::Ordering;
 /// txn = a unique (local to the source) monotonically increasing number that can be used for
 ///       crdt/ordering
 ///       @see https://papers.radixdlt.com/tempo/#logical-clocks
-/// source = the agent making the meta assertion
+/// source = the agent making the meta assertion, as the base58 public key of the agent
 /// signature = the asserting agent's signature of the meta assertion
 pub struct PairMeta {
     pair: String,
     attribute: String,
     value: String,
-    // @TODO implement local transaction ordering
-    // @see https://github.com/holochain/holochain-rust/issues/138
-    // txn: String,
+    txn: u64,
     source: String,
-    // @TODO implement meta data signing
-    // @see https://github.com/holochain/holochain-rust/issues/139
-    // signature: String,
+    signature: String,
 }
 
 impl Ord for PairMeta {
     fn cmp(&self, other: &PairMeta) -> Ordering {
-        // we want to sort by pair hash, then attribute name, then attribute value
-        match self.pair.cmp(&other.pair) {
-            Ordering::Equal => match self.attribute.cmp(&other.attribute) {
-                Ordering::Equal => self.value.cmp(&other.value),
-                Ordering::Greater => Ordering::Greater,
-                Ordering::Less => Ordering::Less,
-            },
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Less => Ordering::Less,
-        }
+        // we want to sort by pair hash, then attribute name, then txn so the latest assertion of
+        // an attribute sorts last, then attribute value and source to break any ties
+        self.pair
+            .cmp(&other.pair)
+            .then_with(|| self.attribute.cmp(&other.attribute))
+            .then_with(|| self.txn.cmp(&other.txn))
+            .then_with(|| self.value.cmp(&other.value))
+            .then_with(|| self.source.cmp(&other.source))
     }
 }
 
@@ -52,13 +45,21 @@ impl PairMeta {
     /// Builds a new PairMeta from EAV and agent keys, where E is an existing Pair
     /// @TODO need a `from()` to build a local meta from incoming network messages
     /// @see https://github.com/holochain/holochain-rust/issues/140
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys can't sign, @see Keys::sign()
     pub fn new(keys: &Keys, pair: &Pair, attribute: &str, value: &str) -> PairMeta {
-        PairMeta {
+        let mut meta = PairMeta {
             pair: pair.key(),
             attribute: attribute.into(),
             value: value.into(),
-            source: keys.node_id(),
-        }
+            txn: keys.next_txn(),
+            source: keys.public_key().to_b58(),
+            signature: String::new(),
+        };
+        meta.signature = keys.sign(meta.signed_content().as_bytes());
+        meta
     }
 
     /// getter for pair clone
@@ -76,14 +77,43 @@ impl PairMeta {
         self.value.clone()
     }
 
+    /// getter for txn
+    pub fn txn(&self) -> u64 {
+        self.txn
+    }
+
     // getter for source clone
     pub fn source(&self) -> String {
         self.source.clone()
     }
 
+    /// getter for signature clone
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    /// everything covered by the signature, i.e. the canonical JSON of everything but the signature
+    fn signed_content(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.signature = String::new();
+        canonical_json(&unsigned)
+    }
+
+    /// true if the signature is a valid signature of this meta by the source
+    pub fn verify(&self) -> bool {
+        Key::from_b58(&self.source)
+            .map(|key| key.verify(self.signed_content().as_bytes(), &self.signature))
+            .unwrap_or(false)
+    }
+
     /// the key for hash table lookups, e.g. table.get_meta()
+    /// hashed with the same algorithm as the pair it is about
     pub fn key(&self) -> String {
-        serializable_to_b58_hash(&self, Hash::SHA2256)
+        canonical_hash(
+            "meta",
+            &self,
+            algorithm_of(&self.pair).unwrap_or(DEFAULT_HASH),
+        )
     }
 }
 
@@ -91,7 +121,7 @@ impl PairMeta {
 pub mod tests {
 
     use super::PairMeta;
-    use agent::keys::tests::test_keys;
+    use agent::keys::tests::{test_keys, test_keys_b};
     use hash_table::pair::tests::{test_pair, test_pair_a, test_pair_b};
     use std::cmp::Ordering;
 
@@ -172,7 +202,57 @@ pub mod tests {
     #[test]
     /// test meta.source()
     fn source() {
-        assert_eq!(test_pair_meta().source(), test_keys().node_id());
+        assert_eq!(test_pair_meta().source(), test_keys().public_key().to_b58());
+    }
+
+    #[test]
+    /// test meta.verify()
+    fn verify() {
+        let m = test_pair_meta();
+        assert!(m.verify());
+
+        // tampering with any field invalidates the signature
+        let mut tampered = m.clone();
+        tampered.value = "tampered value".into();
+        assert!(!tampered.verify());
+
+        let mut tampered = m.clone();
+        tampered.txn += 1;
+        assert!(!tampered.verify());
+
+        // claiming another source invalidates the signature
+        let mut tampered = m.clone();
+        tampered.source = test_keys_b().public_key().to_b58();
+        assert!(!tampered.verify());
+
+        // a garbage source is never valid
+        let mut tampered = m.clone();
+        tampered.source = "not a key".into();
+        assert!(!tampered.verify());
+
+        // a signature by someone else
+        let mut tampered = m.clone();
+        tampered.signature = PairMeta::new(
+            &test_keys_b(),
+            &test_pair(),
+            &m.attribute(),
+            &m.value(),
+        ).signature();
+        assert!(!tampered.verify());
+    }
+
+    #[test]
+    /// test meta.txn()
+    fn txn() {
+        let keys = test_keys();
+        let m1 = PairMeta::new(&keys, &test_pair(), &test_attribute(), &test_value());
+        let m2 = PairMeta::new(&keys, &test_pair(), &test_attribute(), &test_value());
+
+        assert_eq!(1, m1.txn());
+        assert_eq!(2, m2.txn());
+        // the txn makes repeated assertions distinct
+        assert_ne!(m1, m2);
+        assert_ne!(m1.key(), m2.key());
     }
 
     #[test]
@@ -213,5 +293,18 @@ pub mod tests {
         // attribute value with operators
         assert!(m_1ax < m_1ay);
         assert!(m_1ay > m_1ax);
+
+        // sort by txn before attribute value
+        let keys = test_keys();
+        let m_1ay_first = PairMeta::new(&keys, &p1, "a", "y");
+        let m_1ax_second = PairMeta::new(&keys, &p1, "a", "x");
+        assert_eq!(Ordering::Less, m_1ay_first.cmp(&m_1ax_second));
+        assert!(m_1ay_first < m_1ax_second);
+
+        // txn doesn't override pair key or attribute name
+        let m_2ax_third = PairMeta::new(&keys, &p2, "a", "x");
+        let m_1bx_fourth = PairMeta::new(&keys, &p1, "b", "x");
+        assert!(m_2ax_third > m_1ax);
+        assert!(m_1ax_second < m_1bx_fourth);
     }
 }
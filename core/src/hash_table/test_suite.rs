@@ -0,0 +1,738 @@
+//! conformance tests that every HashTable implementation must pass
+//! each check panics with a descriptive message on failure, so they can be called from a #[test]
+//! against any implementation, e.g. `test_suite::run_all(&mut MyTable::new())`
+//! available to other crates with the `test-suite` feature
+
+use agent::keys::{Keys, SEED_LENGTH};
+use chain::Chain;
+use chrono::{TimeZone, Utc};
+use clock::ManualClock;
+use error::HolochainError;
+use hash_table::{
+    entry::Entry,
+    memory::MemTable,
+    pair::Pair,
+    pair_meta::PairMeta,
+    status::{CRUDStatus, LINK_NAME, STATUS_NAME},
+    HashTable, HashTableOp,
+};
+use serde_json;
+use std::sync::{Arc, RwLock};
+
+/// keys asserting all the metadata in the suite
+fn suite_keys() -> Keys {
+    Keys::from_seed(&[0x5e; SEED_LENGTH])
+}
+
+/// builds a valid pair with unique content so that checks sharing a table don't interfere
+fn suite_pair(content: &str) -> Pair {
+    // a fixed clock keeps pairs with the same content identical
+    let chain = Chain::new(Arc::new(RwLock::new(MemTable::new())))
+        .with_clock(Arc::new(ManualClock::new(Utc.timestamp(0, 0))));
+    Pair::new(&chain, Entry::new("testSuiteType", content))
+}
+
+/// the attribute and value of each meta, ignoring when and by whom it was asserted
+fn attribute_values(
+    metas: Result<Vec<PairMeta>, HolochainError>,
+) -> Result<Vec<(String, String)>, HolochainError> {
+    metas.map(|metas| {
+        metas
+            .into_iter()
+            .map(|m| (m.attribute(), m.value()))
+            .collect()
+    })
+}
+
+/// builds a pair that fails validation, i.e. its entry doesn't match its header
+pub fn suite_invalid_pair() -> Pair {
+    let pair = suite_pair("valid content");
+    Pair::from_json(&pair.to_json().replace("valid content", "tampered content"))
+        .expect("an unsigned pair with a tampered entry should deserialize")
+}
+
+/// runs every check in the suite against the given table
+/// the table should be empty, setup() and teardown() are called by the suite
+pub fn run_all<T: HashTable>(table: &mut T) {
+    table.setup().expect("setup should not fail");
+
+    missing_lookups(table);
+    pair_round_trip(table);
+    meta_round_trip(table);
+    meta_reassert(table);
+    modify(table);
+    retract(table);
+    modify_then_retract(table);
+    reject(table);
+    links(table);
+    get_pair_meta_sorting(table);
+    status_live(table);
+    status_deleted(table);
+    status_rejected(table);
+    latest_follows_links(table);
+    latest_deleted(table);
+    latest_link_cycle(table);
+    latest_meta(table);
+    latest_follows_latest_link(table);
+    status_invalid(table);
+    commit_invalid(table);
+    apply_batch(table);
+    apply_atomic(table);
+    assert_meta_invalid_signature(table);
+    last_txn(table);
+
+    table.teardown().expect("teardown should not fail");
+}
+
+/// looking up things that were never committed is not an error
+pub fn missing_lookups<T: HashTable>(table: &mut T) {
+    let p = suite_pair("missing_lookups");
+    let m = PairMeta::new(&suite_keys(), &p, STATUS_NAME, "missing");
+
+    assert_eq!(Ok(None), table.get(&p.key()), "get of an unknown key");
+    assert_eq!(
+        Ok(None),
+        table.get_meta(&m.key()),
+        "get_meta of an unknown key"
+    );
+    assert_eq!(
+        Ok(Vec::new()),
+        table.get_pair_meta(&p),
+        "get_pair_meta of an unknown pair",
+    );
+}
+
+/// Pairs round trip through commit() and get()
+pub fn pair_round_trip<T: HashTable>(table: &mut T) {
+    let p = suite_pair("pair_round_trip");
+
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    assert_eq!(Ok(Some(p.clone())), table.get(&p.key()), "get after commit");
+
+    // committing the same pair again is idempotent
+    table
+        .commit(&p)
+        .expect("re-commit of a pair should not fail");
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get(&p.key()),
+        "get after re-commit"
+    );
+}
+
+/// PairMeta round trips through assert_meta() and get_meta()
+pub fn meta_round_trip<T: HashTable>(table: &mut T) {
+    let p = suite_pair("meta_round_trip");
+    let m = PairMeta::new(&suite_keys(), &p, "suite-attribute", "suite value");
+
+    table
+        .assert_meta(m.clone())
+        .expect("asserting meta should not fail");
+    assert_eq!(
+        Ok(Some(m.clone())),
+        table.get_meta(&m.key()),
+        "get_meta after assert"
+    );
+    assert_eq!(
+        Ok(vec![m]),
+        table.get_pair_meta(&p),
+        "get_pair_meta after assert"
+    );
+}
+
+/// asserting identical PairMeta twice is idempotent
+pub fn meta_reassert<T: HashTable>(table: &mut T) {
+    let p = suite_pair("meta_reassert");
+    let m = PairMeta::new(&suite_keys(), &p, "suite-attribute", "suite value");
+
+    table
+        .assert_meta(m.clone())
+        .expect("asserting meta should not fail");
+    table
+        .assert_meta(m.clone())
+        .expect("re-asserting meta should not fail");
+    assert_eq!(
+        Ok(vec![m]),
+        table.get_pair_meta(&p),
+        "get_pair_meta after re-assert"
+    );
+}
+
+/// modify() commits the new pair, marks the old pair MODIFIED and links it to the new pair
+pub fn modify<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p1 = suite_pair("modify old");
+    let p2 = suite_pair("modify new");
+
+    table
+        .commit(&p1)
+        .expect("commit of a valid pair should not fail");
+    table
+        .modify(&keys, &p1, &p2)
+        .expect("modify with valid pairs should not fail");
+
+    assert_eq!(
+        Ok(Some(p1.clone())),
+        table.get(&p1.key()),
+        "old pair after modify"
+    );
+    assert_eq!(
+        Ok(Some(p2.clone())),
+        table.get(&p2.key()),
+        "new pair after modify"
+    );
+    assert_eq!(
+        Ok(vec![
+            (LINK_NAME.to_string(), p2.key()),
+            (
+                STATUS_NAME.to_string(),
+                CRUDStatus::MODIFIED.bits().to_string(),
+            ),
+        ]),
+        attribute_values(table.get_pair_meta(&p1)),
+        "old pair meta after modify",
+    );
+    assert_eq!(
+        Ok(Vec::new()),
+        table.get_pair_meta(&p2),
+        "new pair meta after modify",
+    );
+}
+
+/// retract() marks the pair DELETED without removing it
+pub fn retract<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p = suite_pair("retract");
+
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    table.retract(&keys, &p).expect("retract should not fail");
+
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get(&p.key()),
+        "pair after retract"
+    );
+    assert_eq!(
+        Ok(vec![(
+            STATUS_NAME.to_string(),
+            CRUDStatus::DELETED.bits().to_string(),
+        )]),
+        attribute_values(table.get_pair_meta(&p)),
+        "pair meta after retract",
+    );
+}
+
+/// reject() adds the pair marked REJECTED
+pub fn reject<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p = suite_pair("reject");
+
+    table.reject(&keys, &p).expect("reject should not fail");
+
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get(&p.key()),
+        "pair after reject"
+    );
+    assert_eq!(
+        Ok(vec![(
+            STATUS_NAME.to_string(),
+            CRUDStatus::REJECTED.bits().to_string(),
+        )]),
+        attribute_values(table.get_pair_meta(&p)),
+        "pair meta after reject",
+    );
+}
+
+/// links are live until their latest meta is a tombstone, and only returned for their tag
+pub fn links<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p = suite_pair("links");
+
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    assert_eq!(Ok(vec![]), table.get_links(&p, "tag"), "links before link");
+
+    for target in &["target 1", "target 2"] {
+        table
+            .add_link(&keys, &p, "tag", target)
+            .expect("add_link should not fail");
+    }
+    table
+        .add_link(&keys, &p, "other tag", "target 3")
+        .expect("add_link should not fail");
+    assert_eq!(
+        Ok(vec!["target 1".to_string(), "target 2".to_string()]),
+        table.get_links(&p, "tag"),
+        "links after link",
+    );
+
+    table
+        .remove_link(&keys, &p, "tag", "target 1")
+        .expect("remove_link should not fail");
+    assert_eq!(
+        Ok(vec!["target 2".to_string()]),
+        table.get_links(&p, "tag"),
+        "links after remove_link",
+    );
+    assert_eq!(
+        Ok(vec!["target 3".to_string()]),
+        table.get_links(&p, "other tag"),
+        "links of another tag after remove_link",
+    );
+
+    // the link is live again after its tombstone
+    table
+        .add_link(&keys, &p, "tag", "target 1")
+        .expect("add_link should not fail");
+    assert_eq!(
+        Ok(vec!["target 2".to_string(), "target 1".to_string()]),
+        table.get_links(&p, "tag"),
+        "links after link again",
+    );
+}
+
+/// retracting the new pair of a modification leaves the old pair's link in place
+pub fn modify_then_retract<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p1 = suite_pair("modify_then_retract old");
+    let p2 = suite_pair("modify_then_retract new");
+
+    table
+        .commit(&p1)
+        .expect("commit of a valid pair should not fail");
+    table
+        .modify(&keys, &p1, &p2)
+        .expect("modify with valid pairs should not fail");
+    table.retract(&keys, &p2).expect("retract should not fail");
+
+    assert_eq!(
+        Ok(Some(p2.key())),
+        table
+            .get_latest_meta(&p1, LINK_NAME)
+            .map(|m| m.map(|m| m.value())),
+        "link from old pair after retracting new pair",
+    );
+    assert_eq!(
+        Ok(vec![(
+            STATUS_NAME.to_string(),
+            CRUDStatus::DELETED.bits().to_string(),
+        )]),
+        attribute_values(table.get_pair_meta(&p2)),
+        "new pair meta after retract",
+    );
+}
+
+/// get_pair_meta() only returns meta for the given pair, sorted by attribute then txn regardless
+/// of the order the meta was asserted in
+pub fn get_pair_meta_sorting<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p = suite_pair("get_pair_meta_sorting");
+    let other = suite_pair("get_pair_meta_sorting other");
+
+    let m_b = PairMeta::new(&keys, &p, "b-attribute", "a value");
+    let m_a1 = PairMeta::new(&keys, &p, "a-attribute", "b value");
+    let m_a2 = PairMeta::new(&keys, &p, "a-attribute", "a value");
+    let m_other = PairMeta::new(&keys, &other, "a-attribute", "a value");
+
+    for m in vec![m_b.clone(), m_a2.clone(), m_other, m_a1.clone()] {
+        table
+            .assert_meta(m)
+            .expect("asserting meta should not fail");
+    }
+
+    assert_eq!(
+        Ok(vec![m_a1, m_a2, m_b]),
+        table.get_pair_meta(&p),
+        "get_pair_meta sorting",
+    );
+}
+
+/// builds status meta for a pair
+fn suite_status(pair: &Pair, status: CRUDStatus) -> PairMeta {
+    PairMeta::new(&suite_keys(), pair, STATUS_NAME, &status.bits().to_string())
+}
+
+/// pairs without status meta are LIVE
+pub fn status_live<T: HashTable>(table: &mut T) {
+    let p = suite_pair("status_live");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+
+    assert_eq!(
+        Ok(CRUDStatus::LIVE),
+        table.get_status(&p),
+        "status of a new pair"
+    );
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get_with_status(&p.key(), CRUDStatus::LIVE),
+        "live lookup of a live pair",
+    );
+    assert_eq!(
+        Ok(None),
+        table.get_with_status(&p.key(), CRUDStatus::DELETED),
+        "deleted lookup of a live pair",
+    );
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get_latest(&p.key(), CRUDStatus::LIVE),
+        "latest live lookup of a live pair",
+    );
+}
+
+/// retracted pairs are only returned when DELETED is in the mask
+pub fn status_deleted<T: HashTable>(table: &mut T) {
+    let p = suite_pair("status_deleted");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    table
+        .retract(&suite_keys(), &p)
+        .expect("retract should not fail");
+
+    assert_eq!(
+        Ok(CRUDStatus::DELETED),
+        table.get_status(&p),
+        "status after retract"
+    );
+    assert_eq!(
+        Ok(None),
+        table.get_with_status(&p.key(), CRUDStatus::LIVE),
+        "live lookup of a deleted pair",
+    );
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get_with_status(&p.key(), CRUDStatus::DELETED),
+        "deleted lookup of a deleted pair",
+    );
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get_with_status(&p.key(), CRUDStatus::ANY),
+        "any lookup of a deleted pair",
+    );
+    assert_eq!(
+        Ok(None),
+        table.get_latest(&p.key(), CRUDStatus::LIVE),
+        "latest live lookup of a deleted pair",
+    );
+}
+
+/// rejected pairs are only returned when REJECTED is in the mask
+pub fn status_rejected<T: HashTable>(table: &mut T) {
+    let p = suite_pair("status_rejected");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    table
+        .assert_meta(suite_status(&p, CRUDStatus::REJECTED))
+        .expect("asserting meta should not fail");
+
+    assert_eq!(
+        Ok(CRUDStatus::REJECTED),
+        table.get_status(&p),
+        "status after reject"
+    );
+    assert_eq!(
+        Ok(None),
+        table.get_latest(&p.key(), CRUDStatus::LIVE | CRUDStatus::DELETED),
+        "live or deleted lookup of a rejected pair",
+    );
+    assert_eq!(
+        Ok(Some(p.clone())),
+        table.get_latest(&p.key(), CRUDStatus::REJECTED),
+        "rejected lookup of a rejected pair",
+    );
+}
+
+/// get_latest() follows crud-link meta through every modification
+pub fn latest_follows_links<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p1 = suite_pair("latest_follows_links 1");
+    let p2 = suite_pair("latest_follows_links 2");
+    let p3 = suite_pair("latest_follows_links 3");
+
+    table
+        .commit(&p1)
+        .expect("commit of a valid pair should not fail");
+    table
+        .modify(&keys, &p1, &p2)
+        .expect("modify with valid pairs should not fail");
+    table
+        .modify(&keys, &p2, &p3)
+        .expect("modify with valid pairs should not fail");
+
+    assert_eq!(
+        Ok(None),
+        table.get_with_status(&p1.key(), CRUDStatus::LIVE),
+        "live lookup of a modified pair",
+    );
+    assert_eq!(
+        Ok(Some(p1.clone())),
+        table.get_with_status(&p1.key(), CRUDStatus::MODIFIED),
+        "modified lookup of a modified pair",
+    );
+    assert_eq!(
+        Ok(Some(p3.clone())),
+        table.get_latest(&p1.key(), CRUDStatus::LIVE),
+        "latest lookup from the first pair",
+    );
+    assert_eq!(
+        Ok(Some(p3.clone())),
+        table.get_latest(&p2.key(), CRUDStatus::LIVE),
+        "latest lookup from a middle pair",
+    );
+}
+
+/// get_latest() respects the mask for the end of the modification chain
+pub fn latest_deleted<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p1 = suite_pair("latest_deleted 1");
+    let p2 = suite_pair("latest_deleted 2");
+
+    table
+        .commit(&p1)
+        .expect("commit of a valid pair should not fail");
+    table
+        .modify(&keys, &p1, &p2)
+        .expect("modify with valid pairs should not fail");
+    table.retract(&keys, &p2).expect("retract should not fail");
+
+    assert_eq!(
+        Ok(None),
+        table.get_latest(&p1.key(), CRUDStatus::LIVE),
+        "latest live lookup when the latest pair is deleted",
+    );
+    assert_eq!(
+        Ok(Some(p2.clone())),
+        table.get_latest(&p1.key(), CRUDStatus::DELETED),
+        "latest deleted lookup when the latest pair is deleted",
+    );
+}
+
+/// get_latest() errors rather than looping forever on circular crud-link meta
+pub fn latest_link_cycle<T: HashTable>(table: &mut T) {
+    let p = suite_pair("latest_link_cycle");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    table
+        .assert_meta(suite_status(&p, CRUDStatus::MODIFIED))
+        .expect("asserting meta should not fail");
+    table
+        .assert_meta(PairMeta::new(&suite_keys(), &p, LINK_NAME, &p.key()))
+        .expect("asserting meta should not fail");
+
+    assert!(
+        table.get_latest(&p.key(), CRUDStatus::ANY).is_err(),
+        "latest lookup through a crud-link cycle",
+    );
+}
+
+/// status meta that isn't a CRUDStatus is an error
+pub fn status_invalid<T: HashTable>(table: &mut T) {
+    let p = suite_pair("status_invalid");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    table
+        .assert_meta(PairMeta::new(
+            &suite_keys(),
+            &p,
+            STATUS_NAME,
+            "not a status",
+        ))
+        .expect("asserting meta should not fail");
+
+    assert!(table.get_status(&p).is_err(), "status from invalid meta");
+    assert!(
+        table.get_with_status(&p.key(), CRUDStatus::ANY).is_err(),
+        "lookup with invalid status meta",
+    );
+}
+
+/// invalid pairs are never committed
+pub fn commit_invalid<T: HashTable>(table: &mut T) {
+    let p = suite_invalid_pair();
+    assert!(
+        !p.validate(),
+        "the invalid pair fixture should not validate"
+    );
+
+    assert!(table.commit(&p).is_err(), "commit of an invalid pair");
+    assert_eq!(Ok(None), table.get(&p.key()), "get after failed commit");
+}
+
+/// every op in a successful batch is applied
+pub fn apply_batch<T: HashTable>(table: &mut T) {
+    let p = suite_pair("apply_batch");
+    let m = PairMeta::new(&suite_keys(), &p, "suite-attribute", "suite value");
+
+    table
+        .apply(&[
+            HashTableOp::Commit(p.clone()),
+            HashTableOp::AssertMeta(m.clone()),
+        ])
+        .expect("applying a valid batch should not fail");
+
+    assert_eq!(Ok(Some(p.clone())), table.get(&p.key()), "pair from batch");
+    assert_eq!(
+        Ok(Some(m.clone())),
+        table.get_meta(&m.key()),
+        "meta from batch"
+    );
+}
+
+/// nothing in a batch is applied if any op in it fails
+pub fn apply_atomic<T: HashTable>(table: &mut T) {
+    let p = suite_pair("apply_atomic");
+    let m = PairMeta::new(&suite_keys(), &p, "suite-attribute", "suite value");
+
+    assert!(
+        table
+            .apply(&[
+                HashTableOp::Commit(p.clone()),
+                HashTableOp::AssertMeta(m.clone()),
+                HashTableOp::Commit(suite_invalid_pair()),
+            ])
+            .is_err(),
+        "applying a batch with an invalid op",
+    );
+
+    assert_eq!(Ok(None), table.get(&p.key()), "pair from failed batch");
+    assert_eq!(Ok(None), table.get_meta(&m.key()), "meta from failed batch");
+}
+
+/// get_latest_meta() and get_status() use the meta with the highest txn, not the last asserted
+pub fn latest_meta<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p = suite_pair("latest_meta");
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+
+    let deleted = PairMeta::new(
+        &keys,
+        &p,
+        STATUS_NAME,
+        &CRUDStatus::DELETED.bits().to_string(),
+    );
+    let live = PairMeta::new(&keys, &p, STATUS_NAME, &CRUDStatus::LIVE.bits().to_string());
+    assert!(
+        deleted.txn() < live.txn(),
+        "txn should increase for the same keys"
+    );
+
+    // assert out of order, the LIVE status is still the latest
+    table
+        .assert_meta(live.clone())
+        .expect("asserting meta should not fail");
+    table
+        .assert_meta(deleted)
+        .expect("asserting meta should not fail");
+
+    assert_eq!(
+        Ok(Some(live)),
+        table.get_latest_meta(&p, STATUS_NAME),
+        "latest status meta",
+    );
+    assert_eq!(Ok(CRUDStatus::LIVE), table.get_status(&p), "latest status");
+    assert_eq!(
+        Ok(None),
+        table.get_latest_meta(&p, LINK_NAME),
+        "latest meta for an attribute that was never asserted",
+    );
+}
+
+/// get_latest() follows the latest link when a pair was modified more than once
+pub fn latest_follows_latest_link<T: HashTable>(table: &mut T) {
+    let keys = suite_keys();
+    let p1 = suite_pair("latest_follows_latest_link 1");
+    let p2 = suite_pair("latest_follows_latest_link 2");
+    let p3 = suite_pair("latest_follows_latest_link 3");
+
+    table
+        .commit(&p1)
+        .expect("commit of a valid pair should not fail");
+    table
+        .modify(&keys, &p1, &p2)
+        .expect("modify with valid pairs should not fail");
+    table
+        .modify(&keys, &p1, &p3)
+        .expect("modify with valid pairs should not fail");
+
+    assert_eq!(
+        Ok(Some(p3.clone())),
+        table.get_latest(&p1.key(), CRUDStatus::LIVE),
+        "latest lookup after modifying the same pair twice",
+    );
+}
+
+/// meta with a signature that doesn't verify against its source is never asserted
+pub fn assert_meta_invalid_signature<T: HashTable>(table: &mut T) {
+    let p = suite_pair("assert_meta_invalid_signature");
+    let m = PairMeta::new(&suite_keys(), &p, "suite-attribute", "suite value");
+    let tampered: PairMeta = serde_json::from_str(
+        &serde_json::to_string(&m)
+            .expect("meta should serialize")
+            .replace("suite value", "tampered value"),
+    )
+    .expect("tampered meta should deserialize");
+    assert!(!tampered.verify(), "the tampered meta should not verify");
+
+    assert!(
+        table.assert_meta(tampered.clone()).is_err(),
+        "asserting meta with an invalid signature",
+    );
+    assert_eq!(
+        Ok(None),
+        table.get_meta(&tampered.key()),
+        "get_meta after failed assert",
+    );
+
+    // a bad signature anywhere in a batch fails the whole batch
+    assert!(
+        table
+            .apply(&[
+                HashTableOp::Commit(p.clone()),
+                HashTableOp::AssertMeta(m.clone()),
+                HashTableOp::AssertMeta(tampered),
+            ])
+            .is_err(),
+        "applying a batch with an invalid signature",
+    );
+    assert_eq!(Ok(None), table.get(&p.key()), "pair from failed batch");
+    assert_eq!(Ok(None), table.get_meta(&m.key()), "meta from failed batch");
+}
+
+/// the last txn of a source is the highest txn it asserted, whatever other sources asserted
+pub fn last_txn<T: HashTable>(table: &mut T) {
+    let keys = Keys::from_seed(&[0x7a; SEED_LENGTH]);
+    let other_keys = Keys::from_seed(&[0x7b; SEED_LENGTH]);
+    let source = keys.public_key().to_b58();
+    let p = suite_pair("last_txn");
+
+    assert_eq!(
+        Ok(0),
+        table.last_txn(&source),
+        "last_txn of an unknown source"
+    );
+
+    table
+        .commit(&p)
+        .expect("commit of a valid pair should not fail");
+    keys.observe_txn(10);
+    other_keys.observe_txn(20);
+    for (keys, value) in &[(&keys, "a"), (&keys, "b"), (&other_keys, "c")] {
+        table
+            .assert_meta(PairMeta::new(keys, &p, "last_txn", value))
+            .expect("assert_meta of a valid meta should not fail");
+    }
+    assert_eq!(Ok(12), table.last_txn(&source), "last_txn of a source");
+}
@@ -223,6 +223,7 @@ pub mod tests {
     extern crate test_utils;
     use super::Instance;
     use action::{Action, ActionWrapper};
+    use clock::tests::test_clock;
     use context::Context;
     use holochain_agent::Agent;
     use holochain_dna::{zome::Zome, Dna};
@@ -231,6 +232,7 @@ pub mod tests {
     use persister::SimplePersister;
     use state::State;
     use std::{
+        collections::HashMap,
         sync::{mpsc::channel, Arc, Mutex},
         thread::sleep,
         time::Duration,
@@ -261,6 +263,9 @@ pub mod tests {
                 agent,
                 logger: logger.clone(),
                 persister: Arc::new(Mutex::new(SimplePersister::new())),
+                clock: Arc::new(test_clock()),
+                keys: None,
+                rotation_keys: Arc::new(Mutex::new(HashMap::new())),
             }),
             logger,
         )
@@ -274,9 +279,14 @@ pub mod tests {
 
     /// create a test instance
     pub fn test_instance(dna: Dna) -> Instance {
+        test_instance_with_context(dna, test_context("jane"))
+    }
+
+    /// create a test instance running in the given context
+    pub fn test_instance_with_context(dna: Dna, context: Arc<Context>) -> Instance {
         // Create instance and plug in our DNA
         let mut instance = Instance::new();
-        instance.start_action_loop(test_context("jane"));
+        instance.start_action_loop(context);
 
         let action_wrapper = ActionWrapper::new(Action::InitApplication(dna.clone()));
         instance.dispatch_and_wait(action_wrapper);
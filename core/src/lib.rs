@@ -1,7 +1,9 @@
 #[macro_use]
 extern crate serde_derive;
 extern crate chrono;
+extern crate crypto;
 extern crate multihash;
+extern crate rand;
 extern crate rust_base58;
 extern crate serde;
 extern crate serde_json;
@@ -24,6 +26,7 @@ extern crate holochain_wasm_utils;
 pub mod action;
 pub mod agent;
 pub mod chain;
+pub mod clock;
 pub mod context;
 pub mod error;
 pub mod hash;
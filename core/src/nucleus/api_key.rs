@@ -0,0 +1,109 @@
+use crypto::util::fixed_time_eq;
+use error::HolochainError;
+use rand::{OsRng, Rng};
+use rust_base58::ToBase58;
+use std::fmt;
+
+/// length in bytes of the random key material of an api key
+pub const API_KEY_LENGTH: usize = 32;
+
+/// an api key issued by the agent running the instance to a client of the instance
+/// it lets the client call the functions of a single capability with the ApiKey membrane
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ApiKey {
+    key: String,
+    zome: String,
+    capability: String,
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the key is a secret, it must never end up in logs or errors
+        write!(
+            f,
+            "ApiKey {{ zome: {:?}, capability: {:?} }}",
+            self.zome, self.capability
+        )
+    }
+}
+
+impl ApiKey {
+    /// a new api key with random key material for the capability of the zome
+    pub fn new(zome: &str, capability: &str) -> Result<ApiKey, HolochainError> {
+        let mut bytes = [0; API_KEY_LENGTH];
+        OsRng::new()?.fill_bytes(&mut bytes);
+        Ok(ApiKey {
+            key: bytes.to_base58(),
+            zome: zome.to_string(),
+            capability: capability.to_string(),
+        })
+    }
+
+    /// the base58 key material the client presents
+    pub fn key(&self) -> String {
+        self.key.clone()
+    }
+
+    /// the zome of the capability the key is scoped to
+    pub fn zome(&self) -> String {
+        self.zome.clone()
+    }
+
+    /// the capability the key is scoped to
+    pub fn capability(&self) -> String {
+        self.capability.clone()
+    }
+
+    /// true if presenting the key lets a client call the capability of the zome
+    /// the key is compared in constant time so the time taken doesn't reveal how much of it matched
+    pub fn grants(&self, key: &str, zome: &str, capability: &str) -> bool {
+        // fixed_time_eq can't compare empty slices
+        !key.is_empty()
+            && fixed_time_eq(self.key.as_bytes(), key.as_bytes())
+            && self.zome == zome
+            && self.capability == capability
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::ApiKey;
+
+    /// dummy api key for the test capability of the test zome
+    pub fn test_api_key() -> ApiKey {
+        ApiKey::new("test_zome", "test_cap").expect("api key should be issued")
+    }
+
+    #[test]
+    /// test that api keys are random and scoped to their capability
+    fn new() {
+        let api_key = test_api_key();
+        assert_eq!("test_zome", api_key.zome());
+        assert_eq!("test_cap", api_key.capability());
+        assert_ne!(test_api_key().key(), api_key.key());
+    }
+
+    #[test]
+    /// test that api keys only grant their own capability
+    fn grants() {
+        let api_key = test_api_key();
+        let key = api_key.key();
+        assert!(api_key.grants(&key, "test_zome", "test_cap"));
+        assert!(!api_key.grants(&key, "test_zome", "other_cap"));
+        assert!(!api_key.grants(&key, "other_zome", "test_cap"));
+        assert!(!api_key.grants(&test_api_key().key(), "test_zome", "test_cap"));
+        assert!(!api_key.grants(&key[1..], "test_zome", "test_cap"));
+        assert!(!api_key.grants("", "test_zome", "test_cap"));
+    }
+
+    #[test]
+    /// test that debugging an api key doesn't reveal the key
+    fn debug() {
+        let api_key = test_api_key();
+        assert_eq!(
+            "ApiKey { zome: \"test_zome\", capability: \"test_cap\" }",
+            format!("{:?}", api_key)
+        );
+        assert!(!format!("{:?}", vec![api_key.clone()]).contains(&api_key.key()));
+    }
+}
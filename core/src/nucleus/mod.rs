@@ -1,9 +1,12 @@
+pub mod api_key;
 pub mod memory;
 pub mod ribosome;
 pub mod state;
 
 use context::Context;
 use error::HolochainError;
+use hash_table::links::Link;
+use holochain_dna::zome::capabilities::{Membrane, ReservedCapabilityNames};
 
 use action::{Action, ActionWrapper, NucleusReduceFn};
 use instance::Observer;
@@ -13,6 +16,7 @@ use nucleus::{
 };
 use snowflake;
 use std::{
+    fmt,
     sync::{
         mpsc::{channel, Sender},
         Arc,
@@ -20,6 +24,49 @@ use std::{
     thread,
 };
 
+/// who a zome function call is made by, checked against the membrane of the called capability
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Provenance {
+    /// the agent running the instance, e.g. through its container or Holochain calling callbacks
+    /// passes agent and public membranes, api-key membranes need an issued api key
+    Agent,
+    /// a client of the instance presenting the key of an api key
+    /// passes public membranes and the api-key membranes of capabilities the key was issued for
+    /// the key is checked against the issued api keys when the call is made, see check_caller()
+    ApiKey(String),
+    /// the zome function of the originating zome in the same instance
+    /// passes zome and public membranes
+    Zome(String),
+    /// anyone else, passes public membranes
+    Public,
+}
+
+impl Provenance {
+    /// true if a call made with this provenance may reach a capability with the membrane
+    /// an api key never satisfies an api-key membrane by itself, it must have been issued for
+    /// the capability
+    pub fn satisfies(&self, membrane: &Membrane) -> bool {
+        match (membrane, self) {
+            (Membrane::Public, _) => true,
+            (Membrane::Agent, Provenance::Agent) => true,
+            (Membrane::Zome, Provenance::Zome(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// describes who made a call for error messages without the secret of an api key
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Provenance::Agent => write!(f, "the agent"),
+            Provenance::ApiKey(_) => write!(f, "an api key"),
+            Provenance::Zome(zome) => write!(f, "zome '{}'", zome),
+            Provenance::Public => write!(f, "the public"),
+        }
+    }
+}
+
 /// Struct holding data for requesting the execution of a Zome function (ExecutionZomeFunction Action)
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FunctionCall {
@@ -28,9 +75,15 @@ pub struct FunctionCall {
     pub capability: String,
     pub function: String,
     pub parameters: String,
+    /// the zomes whose functions are waiting on this call, the calling zome last
+    /// empty for calls from outside the instance
+    pub caller_zomes: Vec<String>,
+    /// who the call is made by
+    pub provenance: Provenance,
 }
 
 impl FunctionCall {
+    /// a call made by the agent running the instance
     pub fn new(zome: &str, capability: &str, function: &str, parameters: &str) -> Self {
         FunctionCall {
             id: snowflake::ProcessUniqueId::new(),
@@ -38,13 +91,41 @@ impl FunctionCall {
             capability: capability.to_string(),
             function: function.to_string(),
             parameters: parameters.to_string(),
+            caller_zomes: Vec::new(),
+            provenance: Provenance::Agent,
+        }
+    }
+
+    /// a copy of the call made by someone else
+    pub fn with_provenance(&self, provenance: Provenance) -> Self {
+        FunctionCall {
+            provenance,
+            ..self.clone()
+        }
+    }
+
+    /// a call made by the zome function of the caller to another zome in the same instance
+    pub fn from_zome(
+        caller: &FunctionCall,
+        zome: &str,
+        capability: &str,
+        function: &str,
+        parameters: &str,
+    ) -> Self {
+        let mut caller_zomes = caller.caller_zomes.clone();
+        caller_zomes.push(caller.zome.clone());
+        FunctionCall {
+            caller_zomes,
+            provenance: Provenance::Zome(caller.zome.clone()),
+            ..FunctionCall::new(zome, capability, function, parameters)
         }
     }
 }
 
-/// WIP - Struct for holding data when requesting an Entry Validation (ValidateEntry Action)
+/// Struct for holding data when requesting an Entry Validation (ValidateEntry Action)
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct EntrySubmission {
+    id: snowflake::ProcessUniqueId,
     pub zome_name: String,
     pub type_name: String,
     pub entry_content: String,
@@ -53,6 +134,7 @@ pub struct EntrySubmission {
 impl EntrySubmission {
     pub fn new<S: Into<String>>(zome_name: S, type_name: S, content: S) -> Self {
         EntrySubmission {
+            id: snowflake::ProcessUniqueId::new(),
             zome_name: zome_name.into(),
             type_name: type_name.into(),
             entry_content: content.into(),
@@ -60,6 +142,83 @@ impl EntrySubmission {
     }
 }
 
+/// Struct for holding data when requesting a Link Validation (ValidateLink Action)
+/// the links_to of the base entry type with the tag and target entry type of the link validates it
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkSubmission {
+    id: snowflake::ProcessUniqueId,
+    pub zome_name: String,
+    pub base_type: String,
+    pub target_type: String,
+    pub link: Link,
+}
+
+impl LinkSubmission {
+    pub fn new<S: Into<String>>(zome_name: S, base_type: S, target_type: S, link: Link) -> Self {
+        LinkSubmission {
+            id: snowflake::ProcessUniqueId::new(),
+            zome_name: zome_name.into(),
+            base_type: base_type.into(),
+            target_type: target_type.into(),
+            link,
+        }
+    }
+}
+
+/// Struct holding the outcome of an Entry Validation (ReturnValidationResult Action)
+/// a failed validation is a ValidationFailed error with the message from the zome
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct ValidationResult {
+    submission: EntrySubmission,
+    result: Result<(), HolochainError>,
+}
+
+impl ValidationResult {
+    fn new(submission: EntrySubmission, result: Result<(), HolochainError>) -> Self {
+        ValidationResult { submission, result }
+    }
+
+    /// read only access to submission
+    pub fn submission(&self) -> EntrySubmission {
+        self.submission.clone()
+    }
+
+    /// read only access to result
+    pub fn result(&self) -> Result<(), HolochainError> {
+        self.result.clone()
+    }
+}
+
+/// Struct holding the outcome of a Link Validation (ReturnLinkValidationResult Action)
+/// a failed validation is a ValidationFailed error with the message from the zome
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct LinkValidationResult {
+    submission: LinkSubmission,
+    result: Result<(), HolochainError>,
+}
+
+impl LinkValidationResult {
+    fn new(submission: LinkSubmission, result: Result<(), HolochainError>) -> Self {
+        LinkValidationResult { submission, result }
+    }
+
+    /// read only access to submission
+    pub fn submission(&self) -> LinkSubmission {
+        self.submission.clone()
+    }
+
+    /// read only access to result
+    pub fn result(&self) -> Result<(), HolochainError> {
+        self.result.clone()
+    }
+}
+
+/// the function every entry type validation WASM exports, as validate_dispatch
+/// it receives the entry content and returns nothing for a valid entry or the reason it's invalid
+/// links_to validation WASM exports the same function, receiving the link as JSON
+/// @see Link::to_json()
+pub const VALIDATE_FUNCTION: &str = "validate";
+
 /// Dispatch ExecuteZoneFunction to and block until call has finished.
 pub fn call_zome_and_wait_for_result(
     call: FunctionCall,
@@ -89,6 +248,64 @@ pub fn call_zome_and_wait_for_result(
     receiver.recv().expect("local channel to work")
 }
 
+/// Dispatch ValidateEntry and block until the validation has finished.
+pub fn validate_entry_and_wait_for_result(
+    submission: EntrySubmission,
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+) -> Result<(), HolochainError> {
+    let validate_action_wrapper = ActionWrapper::new(Action::ValidateEntry(submission.clone()));
+
+    // Dispatch action with observer closure that waits for a result in the state
+    let (sender, receiver) = channel();
+    ::instance::dispatch_action_with_observer(
+        action_channel,
+        observer_channel,
+        validate_action_wrapper,
+        move |state: &super::state::State| {
+            if let Some(result) = state.nucleus().validation_result(&submission) {
+                sender
+                    .send(result.clone())
+                    .expect("local channel to be open");
+                true
+            } else {
+                false
+            }
+        },
+    );
+    // Block until we got that result through the channel:
+    receiver.recv().expect("local channel to work")
+}
+
+/// Dispatch ValidateLink and block until the validation has finished.
+pub fn validate_link_and_wait_for_result(
+    submission: LinkSubmission,
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+) -> Result<(), HolochainError> {
+    let validate_action_wrapper = ActionWrapper::new(Action::ValidateLink(submission.clone()));
+
+    // Dispatch action with observer closure that waits for a result in the state
+    let (sender, receiver) = channel();
+    ::instance::dispatch_action_with_observer(
+        action_channel,
+        observer_channel,
+        validate_action_wrapper,
+        move |state: &super::state::State| {
+            if let Some(result) = state.nucleus().link_validation_result(&submission) {
+                sender
+                    .send(result.clone())
+                    .expect("local channel to be open");
+                true
+            } else {
+                false
+            }
+        },
+    );
+    // Block until we got that result through the channel:
+    receiver.recv().expect("local channel to work")
+}
+
 /// Dispatch ExecuteZoneFunction to Instance and block until call has finished.
 /// for test only??
 pub fn call_and_wait_for_result(
@@ -251,6 +468,62 @@ fn reduce_ia(
     }
 }
 
+/// the provenance of the call must satisfy the membrane of the called capability, api keys must
+/// also have been issued for the called capability and persisted
+/// a zome that is waiting on a call can't be called again until the call returns, so call cycles
+/// between zomes are errors rather than unbounded recursion
+/// calls to missing capabilities aren't checked here
+fn check_caller(
+    context: &Context,
+    state: &NucleusState,
+    fc: &FunctionCall,
+) -> Result<(), HolochainError> {
+    if fc.caller_zomes.contains(&fc.zome) {
+        return Err(HolochainError::new(&format!(
+            "Zome '{}' can't be called re-entrantly from {}",
+            fc.zome,
+            fc.caller_zomes.join(" -> "),
+        )));
+    }
+    let membrane = state
+        .dna
+        .as_ref()
+        .and_then(|dna| dna.get_zome(&fc.zome))
+        .and_then(|zome| {
+            zome.capabilities
+                .iter()
+                .find(|capability| capability.name == fc.capability)
+        })
+        .map(|capability| capability.capability.membrane.clone());
+    match (membrane, &fc.provenance) {
+        (Some(Membrane::ApiKey), Provenance::ApiKey(key)) => {
+            let api_keys = context
+                .persister
+                .lock()
+                .expect("owners of the persister Mutex shouldn't panic")
+                .load_api_keys()?;
+            if api_keys
+                .iter()
+                .any(|api_key| api_key.grants(key, &fc.zome, &fc.capability))
+            {
+                Ok(())
+            } else {
+                Err(HolochainError::MembraneRejected(format!(
+                    "Capability '{}' of Zome '{}' has the ApiKey membrane and no such api key was issued for it",
+                    fc.capability, fc.zome,
+                )))
+            }
+        }
+        (Some(ref membrane), provenance) if !provenance.satisfies(membrane) => {
+            Err(HolochainError::MembraneRejected(format!(
+                "Capability '{}' of Zome '{}' has the {:?} membrane and can't be called by {}",
+                fc.capability, fc.zome, membrane, provenance,
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Reduce ExecuteZomeFunction Action
 /// Execute an exposed Zome function in a seperate thread and send the result in
 /// a ReturnZomeFunctionResult Action on success or failure
@@ -267,6 +540,15 @@ fn reduce_ezf(
     };
     let fc = function_call.clone();
 
+    if let Err(err) = check_caller(&context, state, &fc) {
+        action_channel
+            .send(ActionWrapper::new(Action::ReturnZomeFunctionResult(
+                FunctionResult::new(fc, Err(err)),
+            )))
+            .expect("action channel to be open in reducer");
+        return;
+    }
+
     let mut has_error = false;
     let mut result = FunctionResult::new(
         fc.clone(),
@@ -343,31 +625,228 @@ fn reduce_ezf(
     }
 }
 
+/// Execute validation WASM against the input and block until it has finished
+/// the WASM returns nothing for valid input, anything else is why the input is invalid
+fn run_validation_wasm(
+    context: Arc<Context>,
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+    zome_name: &str,
+    code: Vec<u8>,
+    input: &str,
+) -> Result<(), HolochainError> {
+    let call = FunctionCall::new(
+        zome_name,
+        ReservedCapabilityNames::MissingNo.as_str(),
+        VALIDATE_FUNCTION,
+        input,
+    );
+    match ribosome::api::call(
+        context,
+        action_channel,
+        observer_channel,
+        code,
+        &call,
+        Some(input.to_string().into_bytes()),
+    ) {
+        Ok(ref runtime) if runtime.result.is_empty() => Ok(()),
+        Ok(runtime) => Err(HolochainError::ValidationFailed(runtime.result)),
+        Err(ref error) => Err(HolochainError::ErrorGeneric(format!("{}", error))),
+    }
+}
+
 /// Reduce ValidateEntry Action
-/// Validate an Entry by calling its validation function
+/// Validate an Entry against the JSON Schema of its entry type, then by executing the validation
+/// WASM of its entry type in a seperate thread and send the result in a ReturnValidationResult
+/// Action on success or failure
+fn reduce_ve(
+    context: Arc<Context>,
+    state: &mut NucleusState,
+    action_wrapper: &ActionWrapper,
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let submission = unwrap_to!(action => Action::ValidateEntry).clone();
+
+    // the result is known straight away unless there is validation WASM to execute
+    let result = match state.dna {
+        Some(ref dna) => match dna.get_zome(&submission.zome_name) {
+            Some(_) => match dna.get_entry_type(&submission.zome_name, &submission.type_name) {
+                // entries must conform to the schema of their type before any WASM runs
+                Some(entry_type) => match entry_type
+                    .schema
+                    .as_ref()
+                    .map_or(Ok(()), |schema| schema.validate(&submission.entry_content))
+                {
+                    Err(message) => Some(Err(HolochainError::ValidationFailed(message))),
+                    // entry types without validation WASM accept every entry
+                    Ok(()) if entry_type.validation.code.is_empty() => Some(Ok(())),
+                    Ok(()) => {
+                        state.validation_results.insert(submission.clone(), None);
+
+                        let action_channel = action_channel.clone();
+                        let tx_observer = observer_channel.clone();
+                        let code = entry_type.validation.code.clone();
+                        let submission = submission.clone();
+
+                        thread::spawn(move || {
+                            let result = run_validation_wasm(
+                                context,
+                                &action_channel,
+                                &tx_observer,
+                                &submission.zome_name,
+                                code,
+                                &submission.entry_content,
+                            );
+
+                            // Send ReturnResult Action
+                            action_channel
+                                .send(ActionWrapper::new(Action::ReturnValidationResult(
+                                    ValidationResult::new(submission, result),
+                                )))
+                                .expect("action channel to be open in reducer");
+                        });
+                        None
+                    }
+                },
+                None => Some(Err(HolochainError::EntryTypeNotFound(format!(
+                    "Entry type '{:?}' not found in Zome '{:?}'",
+                    &submission.type_name, &submission.zome_name
+                )))),
+            },
+            None => Some(Err(HolochainError::ZomeNotFound(format!(
+                "Zome '{:?}' not found",
+                &submission.zome_name
+            )))),
+        },
+        None => Some(Err(HolochainError::DnaMissing)),
+    };
+
+    if let Some(result) = result {
+        action_channel
+            .send(ActionWrapper::new(Action::ReturnValidationResult(
+                ValidationResult::new(submission, result),
+            )))
+            .expect("action channel to be open in reducer");
+    }
+}
+
+/// Reduce ValidateLink Action
+/// Validate a Link against the links_to of its base entry type with the same tag and target entry
+/// type, by executing the validation WASM of the links_to in a seperate thread and send the result
+/// in a ReturnLinkValidationResult Action on success or failure
+/// links that aren't declared by a links_to are invalid
+fn reduce_vl(
+    context: Arc<Context>,
+    state: &mut NucleusState,
+    action_wrapper: &ActionWrapper,
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let submission = unwrap_to!(action => Action::ValidateLink).clone();
+
+    // the result is known straight away unless there is validation WASM to execute
+    let result = match state.dna {
+        Some(ref dna) => match dna.get_zome(&submission.zome_name) {
+            Some(_) => match dna.get_entry_type(&submission.zome_name, &submission.base_type) {
+                Some(entry_type) => match entry_type.links_to.iter().find(|links_to| {
+                    links_to.tag == submission.link.tag
+                        && links_to.target_type == submission.target_type
+                }) {
+                    None => Some(Err(HolochainError::ValidationFailed(format!(
+                        "Entry type '{}' has no '{}' links to entry type '{}'",
+                        &submission.base_type, &submission.link.tag, &submission.target_type
+                    )))),
+                    // links_to without validation WASM accept every link
+                    Some(links_to) if links_to.validation.code.is_empty() => Some(Ok(())),
+                    Some(links_to) => {
+                        state
+                            .link_validation_results
+                            .insert(submission.clone(), None);
+
+                        let action_channel = action_channel.clone();
+                        let tx_observer = observer_channel.clone();
+                        let code = links_to.validation.code.clone();
+                        let submission = submission.clone();
+
+                        thread::spawn(move || {
+                            let result = run_validation_wasm(
+                                context,
+                                &action_channel,
+                                &tx_observer,
+                                &submission.zome_name,
+                                code,
+                                &submission.link.to_json(),
+                            );
+
+                            // Send ReturnResult Action
+                            action_channel
+                                .send(ActionWrapper::new(Action::ReturnLinkValidationResult(
+                                    LinkValidationResult::new(submission, result),
+                                )))
+                                .expect("action channel to be open in reducer");
+                        });
+                        None
+                    }
+                },
+                None => Some(Err(HolochainError::EntryTypeNotFound(format!(
+                    "Entry type '{:?}' not found in Zome '{:?}'",
+                    &submission.base_type, &submission.zome_name
+                )))),
+            },
+            None => Some(Err(HolochainError::ZomeNotFound(format!(
+                "Zome '{:?}' not found",
+                &submission.zome_name
+            )))),
+        },
+        None => Some(Err(HolochainError::DnaMissing)),
+    };
+
+    if let Some(result) = result {
+        action_channel
+            .send(ActionWrapper::new(Action::ReturnLinkValidationResult(
+                LinkValidationResult::new(submission, result),
+            )))
+            .expect("action channel to be open in reducer");
+    }
+}
+
+/// reduce ReturnValidationResult
+/// simply drops the validation result into validation_results state
 #[allow(unknown_lints)]
 #[allow(needless_pass_by_value)]
-fn reduce_ve(
+fn reduce_rvr(
     _context: Arc<Context>,
     state: &mut NucleusState,
     action_wrapper: &ActionWrapper,
     _action_channel: &Sender<ActionWrapper>,
     _observer_channel: &Sender<Observer>,
 ) {
-    let mut _has_entry_type = false;
+    let action = action_wrapper.action();
+    let vr = unwrap_to!(action => Action::ReturnValidationResult);
+    state
+        .validation_results
+        .insert(vr.submission(), Some(vr.result()));
+}
 
-    // must have entry_type
-    if let Some(ref dna) = state.dna {
-        let action = action_wrapper.action();
-        let es = unwrap_to!(action => Action::ValidateEntry);
-        if let Some(ref _wasm) =
-            dna.get_validation_bytecode_for_entry_type(&es.zome_name, &es.type_name)
-        {
-            // TODO #61 validate()
-            // Do same thing as Action::ExecuteZomeFunction
-            _has_entry_type = true;
-        }
-    }
+/// reduce ReturnLinkValidationResult
+/// simply drops the validation result into link_validation_results state
+#[allow(unknown_lints)]
+#[allow(needless_pass_by_value)]
+fn reduce_rlvr(
+    _context: Arc<Context>,
+    state: &mut NucleusState,
+    action_wrapper: &ActionWrapper,
+    _action_channel: &Sender<ActionWrapper>,
+    _observer_channel: &Sender<Observer>,
+) {
+    let action = action_wrapper.action();
+    let lvr = unwrap_to!(action => Action::ReturnLinkValidationResult);
+    state
+        .link_validation_results
+        .insert(lvr.submission(), Some(lvr.result()));
 }
 
 /// reduce ReturnZomeFunctionResult
@@ -395,6 +874,9 @@ fn resolve_reducer(action_wrapper: &ActionWrapper) -> Option<NucleusReduceFn> {
         Action::ExecuteZomeFunction(_) => Some(reduce_ezf),
         Action::ReturnZomeFunctionResult(_) => Some(reduce_rzfr),
         Action::ValidateEntry(_) => Some(reduce_ve),
+        Action::ReturnValidationResult(_) => Some(reduce_rvr),
+        Action::ValidateLink(_) => Some(reduce_vl),
+        Action::ReturnLinkValidationResult(_) => Some(reduce_rlvr),
         _ => None,
     }
 }
@@ -428,14 +910,21 @@ pub fn reduce(
 #[cfg(test)]
 pub mod tests {
     extern crate test_utils;
+    extern crate wabt;
+    use self::wabt::Wat2Wasm;
     use super::*;
     use action::{tests::test_action_wrapper_rzfr, ActionWrapper};
-    use holochain_dna::Dna;
+    use hash_table::links::Link;
+    use holochain_dna::{
+        wasm::DnaWasm,
+        zome::entry_types::{EntrySchema, EntryType, LinksTo},
+        Dna,
+    };
     use instance::{
-        tests::{test_context, test_instance, test_instance_blank},
+        tests::{test_context, test_instance, test_instance_blank, test_instance_with_context},
         Instance,
     };
-    use nucleus::state::tests::test_nucleus_state;
+    use nucleus::{api_key::ApiKey, state::tests::test_nucleus_state};
     use std::sync::{mpsc::channel, Arc};
 
     /// dummy zome name compatible with FunctionCall
@@ -473,6 +962,74 @@ pub mod tests {
         FunctionResult::new(test_function_call(), Ok("foo".to_string()))
     }
 
+    /// where test_validation_wasm() keeps its failure message
+    const TEST_VALIDATION_MESSAGE_OFFSET: usize = 1024;
+
+    /// generates validation WASM that passes or fails every entry with the given message
+    pub fn test_validation_wasm(fail_message: Option<&str>) -> Vec<u8> {
+        let message = fail_message.unwrap_or_default();
+        Wat2Wasm::new()
+            .canonicalize_lebs(false)
+            .write_debug_names(true)
+            .convert(format!(
+                r#"
+(module
+
+    (memory 1)
+    (export "memory" (memory 0))
+
+    (func
+        (export "{}_dispatch")
+        (param $allocation i32)
+        (result i32)
+
+        (i32.const {})
+    )
+
+    (data (i32.const {}) "{}")
+)
+                "#,
+                VALIDATE_FUNCTION,
+                // encoded allocation of the message, clear of the input written at offset 0
+                (TEST_VALIDATION_MESSAGE_OFFSET << 16) | message.len(),
+                TEST_VALIDATION_MESSAGE_OFFSET,
+                message,
+            ))
+            .expect("string literal should be valid WAT")
+            .as_ref()
+            .to_vec()
+    }
+
+    /// dummy DNA with a test_zome that has an entry type for each kind of validation
+    /// "valid" passes every entry, "invalid" fails every entry, "unvalidated" has no validation
+    pub fn test_validation_dna() -> Dna {
+        let mut dna = test_utils::create_test_dna_with_wat("test_zome", "test_cap", None);
+        for (name, code) in vec![
+            ("valid", test_validation_wasm(None)),
+            ("invalid", test_validation_wasm(Some("invalid entry"))),
+            ("unvalidated", Vec::new()),
+        ] {
+            let mut entry_type = EntryType::new();
+            entry_type.name = name.into();
+            entry_type.validation = DnaWasm { code };
+            dna.zomes[0].entry_types.push(entry_type);
+        }
+        dna
+    }
+
+    /// validates an entry of the given zome and type with a test_validation_dna() instance
+    fn test_validate(
+        instance: &Instance,
+        zome: &str,
+        entry_type: &str,
+    ) -> Result<(), HolochainError> {
+        validate_entry_and_wait_for_result(
+            EntrySubmission::new(zome, entry_type, "some content"),
+            &instance.action_channel(),
+            &instance.observer_channel(),
+        )
+    }
+
     #[test]
     /// test the equality and uniqueness of function calls (based on internal snowflakes)
     fn test_function_call_eq() {
@@ -498,6 +1055,190 @@ pub mod tests {
         assert_eq!(test_function_result().result(), Ok("foo".to_string()));
     }
 
+    #[test]
+    /// test the equality and uniqueness of entry submissions (based on internal snowflakes)
+    fn test_entry_submission_eq() {
+        let es1 = EntrySubmission::new("zome", "type", "content");
+        let es2 = EntrySubmission::new("zome", "type", "content");
+
+        assert_eq!(es1, es1);
+        assert_ne!(es1, es2);
+    }
+
+    #[test]
+    /// test that validation WASM results end up in the nucleus state
+    fn validate_entry() {
+        let instance = test_instance(test_validation_dna());
+
+        assert_eq!(Ok(()), test_validate(&instance, "test_zome", "valid"));
+        assert_eq!(
+            Err(HolochainError::ValidationFailed("invalid entry".into())),
+            test_validate(&instance, "test_zome", "invalid")
+        );
+        assert_eq!(Ok(()), test_validate(&instance, "test_zome", "unvalidated"));
+
+        // every result is recorded against its submission
+        let results = instance.state().nucleus().validation_results.clone();
+        assert_eq!(3, results.len());
+        assert!(results.values().all(|result| result.is_some()));
+    }
+
+    #[test]
+    /// test that entries are validated against the schema of their type before the WASM runs
+    fn validate_entry_schema() {
+        let mut dna = test_validation_dna();
+        let mut entry_type = EntryType::new();
+        entry_type.name = "structured".into();
+        let schema = r#"{
+            "type": "object",
+            "properties": { "title": { "type": "string" } },
+            "required": ["title"]
+        }"#;
+        entry_type.schema = Some(
+            EntrySchema::new(serde_json::from_str(schema).expect("the schema should be JSON"))
+                .expect("the schema should be valid"),
+        );
+        entry_type.validation = DnaWasm {
+            code: test_validation_wasm(Some("invalid entry")),
+        };
+        dna.zomes[0].entry_types.push(entry_type);
+        let instance = test_instance(dna);
+
+        let validate = |content: &str| {
+            validate_entry_and_wait_for_result(
+                EntrySubmission::new("test_zome", "structured", content),
+                &instance.action_channel(),
+                &instance.observer_channel(),
+            )
+        };
+
+        // conforming content gets as far as the WASM
+        assert_eq!(
+            Err(HolochainError::ValidationFailed("invalid entry".into())),
+            validate(r#"{"title":"hello"}"#)
+        );
+
+        // anything else fails on the schema
+        for content in &[r#"{"title":1}"#, "{}", "not json"] {
+            match validate(content) {
+                Err(HolochainError::ValidationFailed(ref message)) => {
+                    assert_ne!("invalid entry", message)
+                }
+                result => panic!("unexpected validation result {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    /// test that links are validated by the links_to of their base entry type
+    fn validate_link() {
+        let mut dna = test_validation_dna();
+        for (tag, code) in vec![
+            ("valid", test_validation_wasm(None)),
+            ("invalid", test_validation_wasm(Some("invalid link"))),
+            ("unvalidated", Vec::new()),
+        ] {
+            let mut links_to = LinksTo::new();
+            links_to.target_type = "unvalidated".into();
+            links_to.tag = tag.into();
+            links_to.validation = DnaWasm { code };
+            dna.zomes[0]
+                .entry_types
+                .iter_mut()
+                .find(|entry_type| entry_type.name == "valid")
+                .expect("the dna should have a valid entry type")
+                .links_to
+                .push(links_to);
+        }
+        let instance = test_instance(dna);
+
+        let validate = |base_type: &str, tag: &str, target_type: &str| {
+            validate_link_and_wait_for_result(
+                LinkSubmission::new(
+                    "test_zome",
+                    base_type,
+                    target_type,
+                    Link::new("base", "target", tag),
+                ),
+                &instance.action_channel(),
+                &instance.observer_channel(),
+            )
+        };
+
+        assert_eq!(Ok(()), validate("valid", "valid", "unvalidated"));
+        assert_eq!(
+            Err(HolochainError::ValidationFailed("invalid link".into())),
+            validate("valid", "invalid", "unvalidated")
+        );
+        assert_eq!(Ok(()), validate("valid", "unvalidated", "unvalidated"));
+
+        // links must be declared with the same tag and target type by the base type
+        assert_eq!(
+            Err(HolochainError::ValidationFailed(
+                "Entry type 'valid' has no 'valid' links to entry type 'valid'".into()
+            )),
+            validate("valid", "valid", "valid")
+        );
+        assert_eq!(
+            Err(HolochainError::ValidationFailed(
+                "Entry type 'invalid' has no 'valid' links to entry type 'unvalidated'".into()
+            )),
+            validate("invalid", "valid", "unvalidated")
+        );
+        assert_eq!(
+            Err(HolochainError::EntryTypeNotFound(
+                "Entry type '\"xxx\"' not found in Zome '\"test_zome\"'".into()
+            )),
+            validate("xxx", "valid", "unvalidated")
+        );
+    }
+
+    #[test]
+    /// test that validating against missing DNA, zomes or entry types returns the correct errors
+    fn validate_entry_not_found() {
+        let instance = test_instance(test_validation_dna());
+        assert_eq!(
+            Err(HolochainError::EntryTypeNotFound(
+                "Entry type '\"xxx\"' not found in Zome '\"test_zome\"'".into()
+            )),
+            test_validate(&instance, "test_zome", "xxx")
+        );
+        assert_eq!(
+            Err(HolochainError::ZomeNotFound(
+                "Zome '\"xxx\"' not found".into()
+            )),
+            test_validate(&instance, "xxx", "valid")
+        );
+
+        let mut instance = Instance::new();
+        instance.start_action_loop(test_context("jane"));
+        assert_eq!(
+            Err(HolochainError::DnaMissing),
+            test_validate(&instance, "test_zome", "valid")
+        );
+    }
+
+    #[test]
+    /// test for returning validation result actions
+    fn test_reduce_rvr() {
+        let instance = test_instance_blank();
+        let mut state = test_nucleus_state();
+        let submission = EntrySubmission::new("zome", "type", "content");
+        let action_wrapper = ActionWrapper::new(Action::ReturnValidationResult(
+            ValidationResult::new(submission.clone(), Ok(())),
+        ));
+
+        assert_eq!(None, state.validation_result(&submission));
+        reduce_rvr(
+            test_context("jimmy"),
+            &mut state,
+            &action_wrapper,
+            &instance.action_channel(),
+            &instance.observer_channel(),
+        );
+        assert_eq!(Some(Ok(())), state.validation_result(&submission));
+    }
+
     #[test]
     /// smoke test the init of a nucleus
     fn can_instantiate_nucleus_state() {
@@ -699,6 +1440,140 @@ pub mod tests {
         }
     }
 
+    #[test]
+    /// tests that zomes can only call zomes that aren't waiting on the call through capabilities
+    /// with a zome or public membrane
+    fn call_ribosome_function_from_zome() {
+        let mut dna = test_utils::create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::Zome;
+        let mut instance = test_instance(dna);
+
+        let outer = FunctionCall::new("outer_zome", "outer_cap", "main", "");
+        let call = FunctionCall::from_zome(&outer, "test_zome", "test_cap", "main", "");
+        assert_eq!(vec!["outer_zome".to_string()], call.caller_zomes);
+        assert_eq!(
+            Ok("1337".to_string()),
+            super::call_and_wait_for_result(call.clone(), &mut instance)
+        );
+
+        // test_zome is waiting on the inner call
+        let inner = FunctionCall::from_zome(&call, "outer_zome", "outer_cap", "main", "");
+        let cycle = FunctionCall::from_zome(&inner, "test_zome", "test_cap", "main", "");
+        assert_eq!(
+            Err(HolochainError::new(
+                "Zome 'test_zome' can't be called re-entrantly from outer_zome -> test_zome -> outer_zome"
+            )),
+            super::call_and_wait_for_result(cycle, &mut instance)
+        );
+
+        // the agent can't call capabilities with a zome membrane
+        let call = FunctionCall::new("test_zome", "test_cap", "main", "");
+        assert!(super::call_and_wait_for_result(call, &mut instance).is_err());
+
+        // zomes can't call capabilities with an agent membrane
+        let mut dna = test_utils::create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::Agent;
+        let mut instance = test_instance(dna);
+        let call = FunctionCall::new("test_zome", "test_cap", "main", "");
+        assert!(super::call_and_wait_for_result(call, &mut instance).is_ok());
+        let call = FunctionCall::from_zome(&outer, "test_zome", "test_cap", "main", "");
+        assert_eq!(Provenance::Zome("outer_zome".into()), call.provenance);
+        assert!(super::call_and_wait_for_result(call, &mut instance).is_err());
+    }
+
+    #[test]
+    /// tests which provenances satisfy which membranes
+    fn provenance_satisfies() {
+        let provenances = vec![
+            Provenance::Agent,
+            Provenance::ApiKey("key".into()),
+            Provenance::Zome("zome".into()),
+            Provenance::Public,
+        ];
+        for (membrane, satisfied) in vec![
+            (Membrane::Public, vec![true, true, true, true]),
+            (Membrane::Agent, vec![true, false, false, false]),
+            // api keys are only checked against the issued api keys when calls are made
+            (Membrane::ApiKey, vec![false, false, false, false]),
+            (Membrane::Zome, vec![false, false, true, false]),
+        ] {
+            for (provenance, satisfies) in provenances.iter().zip(satisfied) {
+                assert_eq!(
+                    satisfies,
+                    provenance.satisfies(&membrane),
+                    "{:?} {:?}",
+                    provenance,
+                    membrane
+                );
+            }
+        }
+    }
+
+    #[test]
+    /// tests that calls are rejected unless their provenance satisfies the membrane
+    fn call_ribosome_function_membrane() {
+        let mut dna = test_utils::create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::ApiKey;
+        let context = test_context("jane");
+        let api_key = ApiKey::new("test_zome", "test_cap").unwrap();
+        let other_key = ApiKey::new("test_zome", "other_cap").unwrap();
+        context
+            .persister
+            .lock()
+            .unwrap()
+            .save_api_keys(vec![api_key.clone(), other_key.clone()])
+            .unwrap();
+        let mut instance = test_instance_with_context(dna, context);
+        let call = FunctionCall::new("test_zome", "test_cap", "main", "");
+
+        assert_eq!(
+            Ok("1337".to_string()),
+            super::call_and_wait_for_result(
+                call.with_provenance(Provenance::ApiKey(api_key.key())),
+                &mut instance
+            )
+        );
+        // keys must have been issued for the capability
+        for key in vec!["key".to_string(), other_key.key()] {
+            assert_eq!(
+                Err(HolochainError::MembraneRejected(
+                    "Capability 'test_cap' of Zome 'test_zome' has the ApiKey membrane and no such api key was issued for it".into()
+                )),
+                super::call_and_wait_for_result(
+                    call.with_provenance(Provenance::ApiKey(key)),
+                    &mut instance
+                )
+            );
+        }
+        assert_eq!(
+            Err(HolochainError::MembraneRejected(
+                "Capability 'test_cap' of Zome 'test_zome' has the ApiKey membrane and can't be called by the public".into()
+            )),
+            super::call_and_wait_for_result(call.with_provenance(Provenance::Public), &mut instance)
+        );
+        // not even the agent can call without an api key
+        assert_eq!(
+            Err(HolochainError::MembraneRejected(
+                "Capability 'test_cap' of Zome 'test_zome' has the ApiKey membrane and can't be called by the agent".into()
+            )),
+            super::call_and_wait_for_result(call.with_provenance(Provenance::Agent), &mut instance)
+        );
+
+        // rejecting an api key never reveals it
+        let mut dna = test_utils::create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::Agent;
+        let mut instance = test_instance(dna);
+        assert_eq!(
+            Err(HolochainError::MembraneRejected(
+                "Capability 'test_cap' of Zome 'test_zome' has the Agent membrane and can't be called by an api key".into()
+            )),
+            super::call_and_wait_for_result(
+                call.with_provenance(Provenance::ApiKey(api_key.key())),
+                &mut instance
+            )
+        );
+    }
+
     #[test]
     /// tests that calling the wrong zome/capability returns the correct errors
     fn call_wrong_ribosome_function() {
@@ -0,0 +1,182 @@
+use nucleus::{
+    call_zome_and_wait_for_result,
+    ribosome::api::{runtime_allocate_encode_str, runtime_args_to_utf8, HcApiReturnCode, Runtime},
+    FunctionCall,
+};
+use serde_json;
+use std::error::Error;
+use wasmi::{RuntimeArgs, RuntimeValue, Trap};
+
+/// Struct for input data received when Call API function is invoked
+#[derive(Deserialize, Default, Debug, Serialize)]
+struct CallArgs {
+    zome_name: String,
+    cap_name: String,
+    fn_name: String,
+    fn_args: String,
+}
+
+/// HcApiFuncIndex::CALL function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: r#"{"zome_name":"a","cap_name":"b","fn_name":"c","fn_args":"{}"}"#
+/// calls a function of another zome in the same instance, only capabilities with a zome or public
+/// membrane can be called and a zome that is waiting on a call can't be called until it returns
+/// Returns the result of the called function or an HcApiReturnCode as I32 on failure
+pub fn invoke_call(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    // deserialize args
+    let args_str = runtime_args_to_utf8(&runtime, &args);
+    let call_input: CallArgs = match serde_json::from_str(&args_str) {
+        Ok(call_input) => call_input,
+        // Exit on error
+        Err(_) => {
+            // Return Error code in i32 format
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorSerdeJson as i32,
+            )));
+        }
+    };
+
+    let function_call = FunctionCall::from_zome(
+        &runtime.function_call,
+        &call_input.zome_name,
+        &call_input.cap_name,
+        &call_input.fn_name,
+        &call_input.fn_args,
+    );
+
+    // the called function runs in its own thread so the action loop keeps going while we block
+    match call_zome_and_wait_for_result(
+        function_call,
+        &runtime.action_channel,
+        &runtime.observer_channel,
+    ) {
+        Ok(mut result) => {
+            // the result read from the memory of the called function may keep its terminator
+            if result.ends_with('\u{0}') {
+                result.pop();
+            }
+            runtime_allocate_encode_str(runtime, &result)
+        }
+        Err(err) => {
+            let _ = runtime.context.log(&format!(
+                "call to {}/{}/{} failed: {}",
+                call_input.zome_name,
+                call_input.cap_name,
+                call_input.fn_name,
+                err.description()
+            ));
+            Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test_utils;
+
+    use super::CallArgs;
+    use holochain_dna::zome::capabilities::Membrane;
+    use instance::{
+        tests::{test_instance, TestLogger},
+        Instance,
+    };
+    use nucleus::ribosome::api::tests::{
+        test_zome_api_function_call, test_zome_api_function_dna, test_zome_api_function_wasm,
+    };
+    use serde_json;
+    use std::sync::{Arc, Mutex};
+
+    /// call args for a function of a zome
+    fn call_args_bytes(zome_name: &str, cap_name: &str) -> Vec<u8> {
+        let args = CallArgs {
+            zome_name: zome_name.into(),
+            cap_name: cap_name.into(),
+            fn_name: "main".into(),
+            fn_args: "".into(),
+        };
+        serde_json::to_string(&args)
+            .expect("args should serialize")
+            .into_bytes()
+    }
+
+    /// test instance for the call API function
+    /// the "callee_zome" zome returns 1337 from main in its "zome_cap" capability with a zome
+    /// membrane and its "agent_cap" capability with an agent membrane
+    fn test_call_instance() -> (Instance, Vec<u8>) {
+        let wasm = test_zome_api_function_wasm("call");
+        let mut dna = test_zome_api_function_dna(&wasm);
+        let mut callee = test_utils::create_test_dna_with_wat("callee_zome", "zome_cap", None)
+            .zomes
+            .remove(0);
+        callee.capabilities[0].capability.membrane = Membrane::Zome;
+        let mut agent_cap = callee.capabilities[0].clone();
+        agent_cap.name = "agent_cap".into();
+        agent_cap.capability.membrane = Membrane::Agent;
+        callee.capabilities.push(agent_cap);
+        dna.zomes.push(callee);
+        (test_instance(dna), wasm)
+    }
+
+    /// asserts that the call logged a failure containing the reason
+    fn assert_call_failed(runtime_result: &str, logger: &Arc<Mutex<TestLogger>>, reason: &str) {
+        assert_eq!(runtime_result, "");
+        let log = logger.lock().unwrap().log.clone();
+        assert!(
+            log.iter().any(|msg| msg.contains(reason)),
+            "{:?} should contain {}",
+            log,
+            reason
+        );
+    }
+
+    #[test]
+    /// test that we can round trip the result of another zome's function through call
+    fn test_call_round_trip() {
+        let (instance, wasm) = test_call_instance();
+
+        let (runtime, _) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            call_args_bytes("callee_zome", "zome_cap"),
+        );
+        assert_eq!(runtime.result, "1337\u{0}");
+    }
+
+    #[test]
+    /// test that only capabilities with a zome or public membrane can be called
+    fn test_call_membrane() {
+        let (instance, wasm) = test_call_instance();
+
+        let (runtime, logger) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            call_args_bytes("callee_zome", "agent_cap"),
+        );
+        assert_call_failed(&runtime.result, &logger, "has the Agent membrane");
+    }
+
+    #[test]
+    /// test that a zome can't call itself while it waits on the call
+    fn test_call_re_entrant() {
+        let (instance, wasm) = test_call_instance();
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, call_args_bytes("test_zome", ""));
+        assert_call_failed(&runtime.result, &logger, "can't be called re-entrantly");
+    }
+
+    #[test]
+    /// test that calls to missing zomes fail
+    fn test_call_missing_zome() {
+        let (instance, wasm) = test_call_instance();
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, call_args_bytes("xxx", "zome_cap"));
+        assert_call_failed(&runtime.result, &logger, "Zome '\"xxx\"' not found");
+    }
+}
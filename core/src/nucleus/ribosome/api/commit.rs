@@ -1,11 +1,19 @@
 use action::{Action, ActionWrapper};
 use agent::state::ActionResponse;
-use nucleus::ribosome::{
-    api::{runtime_allocate_encode_str, runtime_args_to_utf8, HcApiReturnCode, Runtime},
-    callback::{validate_commit::validate_commit, CallbackParams, CallbackResult},
+use error::HolochainError;
+use hash_table::entry::Entry;
+use nucleus::{
+    ribosome::{
+        api::{
+            dispatch_and_wait_for_response, runtime_allocate_encode_str, runtime_args_to_utf8,
+            HcApiReturnCode, Runtime,
+        },
+        callback::{validate_commit::validate_commit, CallbackParams, CallbackResult},
+    },
+    validate_entry_and_wait_for_result, EntrySubmission,
 };
 use serde_json;
-use std::sync::mpsc::channel;
+use std::error::Error;
 use wasmi::{RuntimeArgs, RuntimeValue, Trap};
 
 /// Struct for input data received when Commit API function is invoked
@@ -15,9 +23,63 @@ struct CommitArgs {
     entry_content: String,
 }
 
+/// record an entry that failed validation as REJECTED and return the given error code
+/// the entry is never committed to the source chain
+pub fn reject(
+    runtime: &Runtime,
+    entry: Entry,
+    code: HcApiReturnCode,
+    reason: &str,
+) -> Result<Option<RuntimeValue>, Trap> {
+    let _ = runtime.context.log(&format!(
+        "rejected entry of entry type {}: {}",
+        entry.entry_type(),
+        reason
+    ));
+    if let ActionResponse::Reject(Err(err)) =
+        dispatch_and_wait_for_response(runtime, ActionWrapper::new(Action::Reject(entry)))
+    {
+        let _ = runtime
+            .context
+            .log(&format!("could not record rejected entry: {}", err));
+    }
+    Ok(Some(RuntimeValue::I32(code as i32)))
+}
+
+/// return the error code for an entry that could not be validated against its entry type
+/// only an entry that failed its validation is recorded as REJECTED, an unknown entry type or an
+/// error running the validation says nothing about the entry itself
+pub fn validation_error(
+    runtime: &Runtime,
+    entry: Entry,
+    err: HolochainError,
+) -> Result<Option<RuntimeValue>, Trap> {
+    let code = match err {
+        HolochainError::ValidationFailed(ref reason) => {
+            return reject(
+                runtime,
+                entry,
+                HcApiReturnCode::ErrorValidationResult,
+                reason,
+            )
+        }
+        HolochainError::EntryTypeNotFound(_) => HcApiReturnCode::ErrorEntryTypeNotFound,
+        _ => HcApiReturnCode::Error,
+    };
+    let _ = runtime.context.log(&format!(
+        "could not validate entry of entry type {}: {}",
+        entry.entry_type(),
+        err.description()
+    ));
+    Ok(Some(RuntimeValue::I32(code as i32)))
+}
+
 /// HcApiFuncIndex::COMMIT function code
 /// args: [0] encoded MemoryAllocation as u32
 /// expected complex argument: r#"{"entry_type_name":"post","entry_content":"hello"}"#
+/// the entry type must exist in the zome, otherwise ErrorEntryTypeNotFound is returned
+/// the entry must pass its validation WASM, otherwise the entry is recorded as REJECTED and
+/// ErrorValidationResult is returned
 /// Returns an HcApiReturnCode as I32
 pub fn invoke_commit(
     runtime: &mut Runtime,
@@ -37,53 +99,42 @@ pub fn invoke_commit(
     };
 
     // Create Chain Entry
-    let entry =
-        ::hash_table::entry::Entry::new(&entry_input.entry_type_name, &entry_input.entry_content);
+    let entry = Entry::new(&entry_input.entry_type_name, &entry_input.entry_content);
+
+    // validate the entry against its entry type
+    // an unknown entry type is an EntryTypeNotFound error, a failed validation is ValidationFailed
+    if let Err(err) = validate_entry_and_wait_for_result(
+        EntrySubmission::new(
+            runtime.function_call.zome.clone(),
+            entry_input.entry_type_name,
+            entry_input.entry_content,
+        ),
+        &runtime.action_channel,
+        &runtime.observer_channel,
+    ) {
+        return validation_error(runtime, entry, err);
+    }
 
     // @TODO test that failing validation prevents commits happening
     // @see https://github.com/holochain/holochain-rust/issues/206
-    if let CallbackResult::Fail(_) = validate_commit(
+    if let CallbackResult::Fail(reason) = validate_commit(
         &runtime.action_channel,
         &runtime.observer_channel,
         &runtime.function_call.zome,
         &CallbackParams::ValidateCommit(entry.clone()),
     ) {
-        return Ok(Some(RuntimeValue::I32(
-            HcApiReturnCode::ErrorCallbackResult as i32,
-        )));
+        return reject(
+            runtime,
+            entry,
+            HcApiReturnCode::ErrorCallbackResult,
+            &reason,
+        );
     }
     // anything other than a fail means we should commit the entry
 
-    // Create Commit Action
-    let action_wrapper = ActionWrapper::new(Action::Commit(entry));
-    // Send Action and block for result
-    let (sender, receiver) = channel();
-    ::instance::dispatch_action_with_observer(
-        &runtime.action_channel,
-        &runtime.observer_channel,
-        action_wrapper.clone(),
-        move |state: &::state::State| {
-            let mut actions_copy = state.agent().actions();
-            match actions_copy.remove(&action_wrapper) {
-                Some(v) => {
-                    // @TODO never panic in wasm
-                    // @see https://github.com/holochain/holochain-rust/issues/159
-                    sender
-                        .send(v)
-                        // the channel stays connected until the first message has been sent
-                        // if this fails that means that it was called after having returned done=true
-                        .expect("observer called after done");
-
-                    true
-                }
-                None => false,
-            }
-        },
-    );
-    // TODO #97 - Return error if timeout or something failed
-    // return Err(_);
-
-    let action_result = receiver.recv().expect("observer dropped before done");
+    // Create Commit Action and block for result
+    let action_result =
+        dispatch_and_wait_for_response(runtime, ActionWrapper::new(Action::Commit(entry)));
 
     match action_result {
         ActionResponse::Commit(_) => {
@@ -101,14 +152,35 @@ mod tests {
     extern crate test_utils;
     extern crate wabt;
 
+    use self::wabt::Wat2Wasm;
     use super::CommitArgs;
-    use hash_table::entry::tests::test_entry;
-    use nucleus::ribosome::api::tests::test_zome_api_function_runtime;
+    use agent::state::ActionResponse;
+    use hash_table::{
+        entry::{tests::test_entry, Entry},
+        pair::Pair,
+        status::CRUDStatus,
+        HashTable,
+    };
+    use holochain_dna::{wasm::DnaWasm, zome::entry_types::EntryType};
+    use instance::{tests::test_instance, Instance};
+    use nucleus::{
+        ribosome::api::tests::{
+            test_zome_api_function_call, test_zome_api_function_dna,
+            test_zome_api_function_runtime, test_zome_api_function_wasm,
+        },
+        tests::test_validation_wasm,
+        VALIDATE_FUNCTION,
+    };
     use serde_json;
 
     /// dummy commit args from standard test entry
     pub fn test_args_bytes() -> Vec<u8> {
         let e = test_entry();
+        entry_args_bytes(&e)
+    }
+
+    /// commit args for any entry
+    fn entry_args_bytes(e: &Entry) -> Vec<u8> {
         let args = CommitArgs {
             entry_type_name: e.entry_type().into(),
             entry_content: e.content().into(),
@@ -118,6 +190,85 @@ mod tests {
             .into_bytes()
     }
 
+    /// validation WASM that traps instead of returning a result
+    fn test_trap_wasm() -> Vec<u8> {
+        Wat2Wasm::new()
+            .canonicalize_lebs(false)
+            .write_debug_names(true)
+            .convert(format!(
+                r#"
+(module
+
+    (memory 1)
+    (export "memory" (memory 0))
+
+    (func
+        (export "{}_dispatch")
+        (param $allocation i32)
+        (result i32)
+
+        (unreachable)
+    )
+)
+                "#,
+                VALIDATE_FUNCTION,
+            ))
+            .expect("string literal should be valid WAT")
+            .as_ref()
+            .to_vec()
+    }
+
+    /// test instance for the commit API function with an entry type that always fails validation
+    /// and an entry type with validation WASM that traps
+    fn test_commit_instance() -> (Instance, Vec<u8>) {
+        let wasm = test_zome_api_function_wasm("commit");
+        let mut dna = test_zome_api_function_dna(&wasm);
+        for (name, code) in vec![
+            ("invalid", test_validation_wasm(Some("invalid entry"))),
+            ("broken", test_trap_wasm()),
+        ] {
+            let mut entry_type = EntryType::new();
+            entry_type.name = name.into();
+            entry_type.validation = DnaWasm { code };
+            dna.zomes[0].entry_types.push(entry_type);
+        }
+        (test_instance(dna), wasm)
+    }
+
+    /// the pairs the instance recorded as REJECTED
+    fn rejected_pairs(instance: &Instance) -> Vec<Pair> {
+        instance
+            .state()
+            .agent()
+            .actions()
+            .values()
+            .filter_map(|response| match response {
+                ActionResponse::Reject(Ok(pair)) => Some(pair.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// asserts that the entry was rejected by the instance rather than committed
+    fn assert_rejected(instance: &Instance, entry: &Entry) {
+        let agent = instance.state().agent();
+        let rejected = rejected_pairs(instance);
+        assert_eq!(1, rejected.len());
+        assert_eq!(entry, rejected[0].entry());
+
+        // the rejected entry is kept as REJECTED but never reaches the source chain
+        assert_eq!(None, agent.top_pair());
+        assert_eq!(
+            Ok(CRUDStatus::REJECTED),
+            agent
+                .chain()
+                .table()
+                .read()
+                .unwrap()
+                .get_status(&rejected[0]),
+        );
+    }
+
     #[test]
     /// test that we can round trip bytes through a commit action and get the result from WASM
     fn test_commit_round_trip() {
@@ -129,4 +280,63 @@ mod tests {
         );
     }
 
+    #[test]
+    /// test that entries of a type that is not in the zome are neither committed nor rejected
+    fn test_commit_unknown_entry_type() {
+        let (instance, wasm) = test_commit_instance();
+        let entry = Entry::new("unknownEntryType", "some content");
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, entry_args_bytes(&entry));
+
+        // an error code has no result
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("Entry type '\"unknownEntryType\"' not found")));
+        assert_eq!(None, instance.state().agent().top_pair());
+        assert!(rejected_pairs(&instance).is_empty());
+    }
+
+    #[test]
+    /// test that entries are not rejected when their validation WASM traps
+    fn test_commit_broken_validation() {
+        let (instance, wasm) = test_commit_instance();
+        let entry = Entry::new("broken", "some content");
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, entry_args_bytes(&entry));
+
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("could not validate entry of entry type broken")));
+        assert_eq!(None, instance.state().agent().top_pair());
+        assert!(rejected_pairs(&instance).is_empty());
+    }
+
+    #[test]
+    /// test that entries failing the validation of their type are rejected
+    fn test_commit_invalid_entry() {
+        let (instance, wasm) = test_commit_instance();
+        let entry = Entry::new("invalid", "some content");
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, entry_args_bytes(&entry));
+
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("invalid entry")));
+        assert_rejected(&instance, &entry);
+    }
 }
@@ -57,11 +57,9 @@ pub fn invoke_get(runtime: &mut Runtime, args: &RuntimeArgs) -> Result<Option<Ru
     let action_result = receiver.recv().expect("observer dropped before done");
 
     match action_result {
-        ActionResponse::Get(maybe_pair) => {
+        ActionResponse::Get(_) => {
             // serialize, allocate and encode result
-            let pair_str = maybe_pair.map(|p| p.to_json()).unwrap_or_default();
-
-            runtime_allocate_encode_str(runtime, &pair_str)
+            runtime_allocate_encode_str(runtime, &action_result.to_json())
         }
         _ => Ok(Some(RuntimeValue::I32(
             HcApiReturnCode::ErrorActionResult as i32,
@@ -75,8 +73,13 @@ mod tests {
     extern crate wabt;
 
     use super::GetArgs;
+    use action::{Action, ActionWrapper};
     use hash_table::entry::tests::{test_entry, test_entry_hash};
-    use nucleus::ribosome::api::tests::test_zome_api_function_runtime;
+    use holochain_dna::zome::capabilities::ReservedCapabilityNames;
+    use instance::tests::test_instance;
+    use nucleus::ribosome::api::tests::{
+        test_zome_api_function_call, test_zome_api_function_runtime, test_zome_api_function_wasm,
+    };
     use serde_json;
 
     /// dummy get args from standard test entry
@@ -90,14 +93,41 @@ mod tests {
     #[test]
     /// test that we can round trip bytes through a get action and it comes back from wasm
     fn test_get_round_trip() {
-        let (runtime, _) = test_zome_api_function_runtime("get", test_args_bytes());
-
-        let mut expected = "".to_owned();
-        expected.push_str("{\"header\":{\"entry_type\":\"testEntryType\",\"time\":\"\",\"next\":null,\"entry\":\"");
-        expected.push_str(&test_entry_hash());
-        expected.push_str("\",\"type_next\":null,\"signature\":\"\"},\"entry\":{\"content\":\"test entry content\",\"entry_type\":\"testEntryType\"}}\u{0}");
+        let wasm = test_zome_api_function_wasm("get");
+        let dna = test_utils::create_test_dna_with_wasm(
+            "test_zome".into(),
+            ReservedCapabilityNames::MissingNo.as_str(),
+            wasm.clone(),
+        );
+        let mut instance = test_instance(dna);
+
+        // the entry must be on the source chain before we can get it
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(test_entry())));
+
+        let (runtime, _) = test_zome_api_function_call(&instance, &wasm, test_args_bytes());
+
+        // the header is signed by the agent keys generated for the instance
+        let pair = instance
+            .state()
+            .agent()
+            .top_pair()
+            .expect("the entry should be on the source chain");
+        assert_eq!(&test_entry(), pair.entry());
+        assert_eq!(test_entry_hash(), pair.header().entry());
+        assert!(pair.header().verify_signature());
+
+        let mut expected = pair.to_json();
+        expected.push_str("\u{0}");
 
         assert_eq!(runtime.result, expected);
     }
 
+    #[test]
+    /// test that getting an entry that was never committed returns nothing
+    fn test_get_missing() {
+        let (runtime, _) = test_zome_api_function_runtime("get", test_args_bytes());
+
+        assert_eq!(runtime.result, "\u{0}".to_string());
+    }
+
 }
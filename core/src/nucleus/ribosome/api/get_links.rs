@@ -0,0 +1,112 @@
+use action::{Action, ActionWrapper};
+use agent::state::ActionResponse;
+use hash_table::links::LinkQuery;
+use nucleus::ribosome::api::{
+    dispatch_and_wait_for_response, runtime_allocate_encode_str, runtime_args_to_utf8,
+    HcApiReturnCode, Runtime,
+};
+use serde_json;
+use wasmi::{RuntimeArgs, RuntimeValue, Trap};
+
+/// HcApiFuncIndex::GET_LINKS function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: r#"{"base":"Qm...","tag":"author"}"#
+/// Returns the target entry hashes of every live link from the base with the tag as a JSON array,
+/// oldest first, or an HcApiReturnCode as I32 on failure
+pub fn invoke_get_links(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    // deserialize args
+    let args_str = runtime_args_to_utf8(&runtime, &args);
+    let query: LinkQuery = match serde_json::from_str(&args_str) {
+        Ok(query) => query,
+        // Exit on error
+        Err(_) => {
+            // Return Error code in i32 format
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorSerdeJson as i32,
+            )));
+        }
+    };
+
+    let action_result =
+        dispatch_and_wait_for_response(runtime, ActionWrapper::new(Action::GetLinks(query)));
+
+    match action_result {
+        ActionResponse::GetLinks(Ok(_)) => {
+            // serialize, allocate and encode result
+            runtime_allocate_encode_str(runtime, &action_result.to_json())
+        }
+        _ => {
+            let _ = runtime
+                .context
+                .log(&format!("get_links failed: {}", action_result.to_json()));
+            Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use action::{Action, ActionWrapper};
+    use hash_table::{
+        entry::tests::test_entry_b,
+        links::{
+            tests::{test_link, test_link_query},
+            LinkQuery,
+        },
+    };
+    use nucleus::ribosome::api::{
+        link::tests::test_link_instance,
+        tests::{test_zome_api_function_call, test_zome_api_function_wasm},
+    };
+    use serde_json;
+
+    /// get links args for a query
+    fn query_args_bytes(query: &LinkQuery) -> Vec<u8> {
+        serde_json::to_string(query)
+            .expect("query should serialize")
+            .into_bytes()
+    }
+
+    #[test]
+    /// test that we can round trip the targets of links through get_links
+    fn test_get_links_round_trip() {
+        let mut instance = test_link_instance();
+        let wasm = test_zome_api_function_wasm("get_links");
+
+        let (runtime, _) =
+            test_zome_api_function_call(&instance, &wasm, query_args_bytes(&test_link_query()));
+        assert_eq!(runtime.result, "[]\u{0}");
+
+        instance.dispatch_and_wait(ActionWrapper::new(Action::AddLink(test_link())));
+        let (runtime, _) =
+            test_zome_api_function_call(&instance, &wasm, query_args_bytes(&test_link_query()));
+        assert_eq!(
+            runtime.result,
+            format!(r#"["{}"]"#, test_entry_b().hash()) + "\u{0}",
+        );
+    }
+
+    #[test]
+    /// test that getting links from an entry that isn't on the chain is an error
+    fn test_get_links_missing_base() {
+        let instance = test_link_instance();
+        let (runtime, logger) = test_zome_api_function_call(
+            &instance,
+            &test_zome_api_function_wasm("get_links"),
+            query_args_bytes(&LinkQuery::new("xxx", "tag")),
+        );
+
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("link base xxx is not on the chain")));
+    }
+}
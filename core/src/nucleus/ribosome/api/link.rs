@@ -0,0 +1,230 @@
+use action::{Action, ActionWrapper};
+use agent::state::ActionResponse;
+use error::HolochainError;
+use hash_table::{links::Link, pair::Pair};
+use nucleus::{
+    ribosome::api::{
+        dispatch_and_wait_for_response, get_entry_pair, runtime_args_to_utf8, HcApiReturnCode,
+        Runtime,
+    },
+    validate_link_and_wait_for_result, LinkSubmission,
+};
+use serde_json;
+use std::error::Error;
+use wasmi::{RuntimeArgs, RuntimeValue, Trap};
+
+/// get the pair of the base or target entry of a link
+fn get_link_pair(runtime: &Runtime, entry_hash: &str, end: &str) -> Result<Pair, HolochainError> {
+    match get_entry_pair(runtime, entry_hash) {
+        Some(pair) => Ok(pair),
+        None => Err(HolochainError::new(&format!(
+            "link {} {} not found",
+            end, entry_hash
+        ))),
+    }
+}
+
+/// validate a link against the links_to of its base entry type in the calling zome
+fn validate_link(runtime: &Runtime, link: &Link) -> Result<(), HolochainError> {
+    let base = get_link_pair(runtime, &link.base, "base")?;
+    let target = get_link_pair(runtime, &link.target, "target")?;
+    validate_link_and_wait_for_result(
+        LinkSubmission::new(
+            runtime.function_call.zome.clone(),
+            base.header().entry_type(),
+            target.header().entry_type(),
+            link.clone(),
+        ),
+        &runtime.action_channel,
+        &runtime.observer_channel,
+    )
+}
+
+/// validate the link in the args then dispatch the link action built from it
+/// an invalid link is logged and never reaches the agent
+fn invoke_link_action(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+    link_action: fn(Link) -> Action,
+) -> Result<Option<RuntimeValue>, Trap> {
+    // deserialize args
+    let args_str = runtime_args_to_utf8(&runtime, &args);
+    let link: Link = match serde_json::from_str(&args_str) {
+        Ok(link) => link,
+        // Exit on error
+        Err(_) => {
+            // Return Error code in i32 format
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorSerdeJson as i32,
+            )));
+        }
+    };
+
+    if let Err(err) = validate_link(runtime, &link) {
+        let _ = runtime.context.log(&format!(
+            "invalid link {}: {}",
+            link.to_json(),
+            err.description()
+        ));
+        return Ok(Some(RuntimeValue::I32(
+            HcApiReturnCode::ErrorValidationResult as i32,
+        )));
+    }
+
+    match dispatch_and_wait_for_response(runtime, ActionWrapper::new(link_action(link))) {
+        ActionResponse::AddLink(Ok(())) | ActionResponse::RemoveLink(Ok(())) => {
+            Ok(Some(RuntimeValue::I32(HcApiReturnCode::Success as i32)))
+        }
+        action_result => {
+            let _ = runtime
+                .context
+                .log(&format!("link action failed: {}", action_result.to_json()));
+            Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )))
+        }
+    }
+}
+
+/// HcApiFuncIndex::LINK function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: r#"{"base":"Qm...","target":"Qm...","tag":"author"}"#
+/// base and target are entry hashes, the entry type of the base must declare the tag and the entry
+/// type of the target in its links_to and the link must pass the links_to validation WASM
+/// Returns an HcApiReturnCode as I32
+pub fn invoke_link(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    invoke_link_action(runtime, args, Action::AddLink)
+}
+
+/// HcApiFuncIndex::REMOVE_LINK function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: as per invoke_link
+/// the link is removed with a tombstone, it is validated as per invoke_link
+/// Returns an HcApiReturnCode as I32
+pub fn invoke_remove_link(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    invoke_link_action(runtime, args, Action::RemoveLink)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use action::{Action, ActionWrapper};
+    use hash_table::{
+        entry::tests::{test_entry_a, test_entry_b, test_type_b},
+        links::{
+            tests::{test_link, test_link_query, test_tag},
+            Link,
+        },
+    };
+    use holochain_dna::{wasm::DnaWasm, zome::entry_types::LinksTo};
+    use instance::{tests::test_instance, Instance};
+    use nucleus::{
+        ribosome::api::tests::{
+            test_zome_api_function_call, test_zome_api_function_dna, test_zome_api_function_wasm,
+        },
+        tests::test_validation_wasm,
+    };
+
+    /// test instance for link API functions with test_entry_a() and test_entry_b() committed
+    /// test_entry_a() may link to test_entry_b() with test_tag() or the "invalid" tag, links with
+    /// the "invalid" tag always fail validation
+    pub fn test_link_instance() -> Instance {
+        let wasm = test_zome_api_function_wasm("link");
+        let mut dna = test_zome_api_function_dna(&wasm);
+        for (tag, code) in vec![
+            (test_tag(), Vec::new()),
+            (
+                "invalid".to_string(),
+                test_validation_wasm(Some("invalid link")),
+            ),
+        ] {
+            let mut links_to = LinksTo::new();
+            links_to.target_type = test_type_b();
+            links_to.tag = tag;
+            links_to.validation = DnaWasm { code };
+            dna.zomes[0].entry_types[0].links_to.push(links_to);
+        }
+        let mut instance = test_instance(dna);
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(test_entry_a())));
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(test_entry_b())));
+        instance
+    }
+
+    /// the targets of the links from test_link_query() in the instance
+    pub fn test_link_targets(instance: &Instance) -> Vec<String> {
+        instance
+            .state()
+            .agent()
+            .chain()
+            .get_links(&test_link_query())
+            .expect("the base should be on the chain")
+    }
+
+    /// calls a link API function with a link against the test_link_instance(), returns the log
+    fn test_link_call(instance: &Instance, canonical_name: &str, link: &Link) -> Vec<String> {
+        let (runtime, logger) = test_zome_api_function_call(
+            instance,
+            &test_zome_api_function_wasm(canonical_name),
+            link.to_json().into_bytes(),
+        );
+        // success and error codes both have no result
+        assert_eq!(runtime.result, "");
+        let log = logger.lock().unwrap().log.clone();
+        log
+    }
+
+    #[test]
+    /// test that links declared by the base entry type are added and removed
+    fn test_link_round_trip() {
+        let instance = test_link_instance();
+        assert!(test_link_targets(&instance).is_empty());
+
+        assert!(test_link_call(&instance, "link", &test_link()).is_empty());
+        assert_eq!(vec![test_link().target], test_link_targets(&instance));
+
+        assert!(test_link_call(&instance, "remove_link", &test_link()).is_empty());
+        assert!(test_link_targets(&instance).is_empty());
+    }
+
+    #[test]
+    /// test that invalid links are never added
+    fn test_link_invalid() {
+        let instance = test_link_instance();
+        let base = test_entry_a().hash();
+        let target = test_entry_b().hash();
+
+        for (link, reason) in vec![
+            // failing validation WASM
+            (Link::new(&base, &target, "invalid"), "invalid link"),
+            // not declared by the base entry type
+            (
+                Link::new(&base, &target, "xxx"),
+                "has no 'xxx' links to entry type 'testEntryTypeB'",
+            ),
+            (
+                Link::new(&base, &base, &test_tag()),
+                "links to entry type 'testEntryType'",
+            ),
+            (Link::new(&target, &base, &test_tag()), "not found in Zome"),
+            // not on the chain
+            (
+                Link::new(&base, "xxx", &test_tag()),
+                "link target xxx not found",
+            ),
+        ] {
+            let log = test_link_call(&instance, "link", &link);
+            assert!(
+                log.iter().any(|msg| msg.contains(reason)),
+                "{:?} should contain {}",
+                log,
+                reason
+            );
+        }
+        assert!(test_link_targets(&instance).is_empty());
+    }
+}
@@ -1,16 +1,33 @@
+pub mod call;
 pub mod commit;
 pub mod debug;
 pub mod get;
+pub mod get_links;
+pub mod link;
+pub mod remove_entry;
+pub mod update_entry;
 
-use action::ActionWrapper;
+use action::{Action, ActionWrapper};
+use agent::state::ActionResponse;
 use context::Context;
+use error::HolochainError;
+use hash_table::{pair::Pair, status::CRUDStatus, HashTable};
 use holochain_dna::zome::capabilities::ReservedCapabilityNames;
 use holochain_wasm_utils::{HcApiReturnCode, SinglePageAllocation};
 use instance::Observer;
 use nucleus::{
     memory::SinglePageManager,
     ribosome::{
-        api::{commit::invoke_commit, debug::invoke_debug, get::invoke_get},
+        api::{
+            call::invoke_call,
+            commit::invoke_commit,
+            debug::invoke_debug,
+            get::invoke_get,
+            get_links::invoke_get_links,
+            link::{invoke_link, invoke_remove_link},
+            remove_entry::invoke_remove_entry,
+            update_entry::invoke_update_entry,
+        },
         Defn,
     },
     FunctionCall,
@@ -18,7 +35,10 @@ use nucleus::{
 use num_traits::FromPrimitive;
 use std::{
     str::FromStr,
-    sync::{mpsc::Sender, Arc},
+    sync::{
+        mpsc::{channel, Sender},
+        Arc,
+    },
 };
 use wasmi::{
     self, Error as InterpreterError, Externals, FuncInstance, FuncRef, ImportsBuilder,
@@ -53,6 +73,30 @@ pub enum ZomeAPIFunction {
     /// Get an entry from source chain by key (header hash)
     /// get(key: String) -> Pair
     Get,
+
+    /// Link an entry on the source chain to another entry with a tag
+    /// link(base: String, target: String, tag: String)
+    Link,
+
+    /// Get the targets of every link from an entry with a tag
+    /// get_links(base: String, tag: String) -> Vec<String>
+    GetLinks,
+
+    /// Remove a link added by link
+    /// remove_link(base: String, target: String, tag: String)
+    RemoveLink,
+
+    /// Push a new version of an entry on the source chain
+    /// update_entry(old_hash: String, new_content: String) -> Hash
+    UpdateEntry,
+
+    /// Remove an entry from the source chain with a deletion record
+    /// remove_entry(hash: String) -> Hash
+    RemoveEntry,
+
+    /// Call a function of another zome in the same instance
+    /// call(zome_name: String, cap_name: String, fn_name: String, fn_args: String) -> String
+    Call,
 }
 
 impl Defn for ZomeAPIFunction {
@@ -62,6 +106,12 @@ impl Defn for ZomeAPIFunction {
             ZomeAPIFunction::Debug => "debug",
             ZomeAPIFunction::Commit => "commit",
             ZomeAPIFunction::Get => "get",
+            ZomeAPIFunction::Link => "link",
+            ZomeAPIFunction::GetLinks => "get_links",
+            ZomeAPIFunction::RemoveLink => "remove_link",
+            ZomeAPIFunction::UpdateEntry => "update_entry",
+            ZomeAPIFunction::RemoveEntry => "remove_entry",
+            ZomeAPIFunction::Call => "call",
         }
     }
 
@@ -91,6 +141,24 @@ impl Defn for ZomeAPIFunction {
             // @TODO what should this be?
             // @see https://github.com/holochain/holochain-rust/issues/133
             ZomeAPIFunction::Get => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::Link => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::GetLinks => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::RemoveLink => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::UpdateEntry => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::RemoveEntry => ReservedCapabilityNames::MissingNo,
+            // @TODO what should this be?
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            ZomeAPIFunction::Call => ReservedCapabilityNames::MissingNo,
         }
     }
 }
@@ -102,6 +170,12 @@ impl FromStr for ZomeAPIFunction {
             "debug" => Ok(ZomeAPIFunction::Debug),
             "commit" => Ok(ZomeAPIFunction::Commit),
             "get" => Ok(ZomeAPIFunction::Get),
+            "link" => Ok(ZomeAPIFunction::Link),
+            "get_links" => Ok(ZomeAPIFunction::GetLinks),
+            "remove_link" => Ok(ZomeAPIFunction::RemoveLink),
+            "update_entry" => Ok(ZomeAPIFunction::UpdateEntry),
+            "remove_entry" => Ok(ZomeAPIFunction::RemoveEntry),
+            "call" => Ok(ZomeAPIFunction::Call),
             _ => Err("Cannot convert string to ZomeAPIFunction"),
         }
     }
@@ -119,6 +193,12 @@ impl ZomeAPIFunction {
             ZomeAPIFunction::Debug => invoke_debug,
             ZomeAPIFunction::Commit => invoke_commit,
             ZomeAPIFunction::Get => invoke_get,
+            ZomeAPIFunction::Link => invoke_link,
+            ZomeAPIFunction::GetLinks => invoke_get_links,
+            ZomeAPIFunction::RemoveLink => invoke_remove_link,
+            ZomeAPIFunction::UpdateEntry => invoke_update_entry,
+            ZomeAPIFunction::RemoveEntry => invoke_remove_entry,
+            ZomeAPIFunction::Call => invoke_call,
         }
     }
 }
@@ -160,6 +240,97 @@ pub fn runtime_args_to_utf8(runtime: &Runtime, args: &RuntimeArgs) -> String {
         .unwrap()
 }
 
+/// dispatch an action to the agent and block until the agent has responded to it
+/// the response is mapped by the closure in the observer, so the closure sees the state the
+/// response was given in
+fn dispatch_and_wait_with_state<T, F>(runtime: &Runtime, action_wrapper: ActionWrapper, f: F) -> T
+where
+    T: 'static + Send,
+    F: 'static + Fn(&::state::State, ActionResponse) -> T + Send,
+{
+    // Send Action and block for result
+    let (sender, receiver) = channel();
+    ::instance::dispatch_action_with_observer(
+        &runtime.action_channel,
+        &runtime.observer_channel,
+        action_wrapper.clone(),
+        move |state: &::state::State| {
+            let mut actions_copy = state.agent().actions();
+            match actions_copy.remove(&action_wrapper) {
+                Some(v) => {
+                    // @TODO never panic in wasm
+                    // @see https://github.com/holochain/holochain-rust/issues/159
+                    sender
+                        .send(f(state, v))
+                        // the channel stays connected until the first message has been sent
+                        // if this fails that means that it was called after having returned done=true
+                        .expect("observer called after done");
+
+                    true
+                }
+                None => false,
+            }
+        },
+    );
+    // TODO #97 - Return error if timeout or something failed
+    // return Err(_);
+
+    receiver.recv().expect("observer dropped before done")
+}
+
+/// dispatch an action to the agent and block until the agent has responded to it
+pub fn dispatch_and_wait_for_response(
+    runtime: &Runtime,
+    action_wrapper: ActionWrapper,
+) -> ActionResponse {
+    dispatch_and_wait_with_state(runtime, action_wrapper, |_, response| response)
+}
+
+/// the pair of an entry on the source chain by entry hash, as per the get zome API function
+pub fn get_entry_pair(runtime: &Runtime, entry_hash: &str) -> Option<Pair> {
+    match dispatch_and_wait_for_response(
+        runtime,
+        ActionWrapper::new(Action::Get(entry_hash.into())),
+    ) {
+        ActionResponse::Get(Ok(maybe_pair)) => maybe_pair,
+        _ => None,
+    }
+}
+
+/// the pair of an entry on the source chain by entry hash as per get_entry_pair(), only if the
+/// pair is LIVE
+/// MODIFIED, DELETED and REJECTED pairs are final, they can't be updated or removed again
+pub fn get_live_entry_pair(runtime: &Runtime, entry_hash: &str) -> Result<Pair, HolochainError> {
+    let action_wrapper = ActionWrapper::new(Action::Get(entry_hash.into()));
+    let entry_hash = entry_hash.to_string();
+    // the status is read from the same state as the response to the get
+    dispatch_and_wait_with_state(
+        runtime,
+        action_wrapper,
+        move |state, response| match response {
+            ActionResponse::Get(Ok(Some(pair))) => state
+                .agent()
+                .chain()
+                .table()
+                .read()
+                .expect("owners of the table RwLock shouldn't panic")
+                .get_with_status(&pair.key(), CRUDStatus::LIVE)
+                .and_then(|live| match live {
+                    Some(_) => Ok(pair),
+                    None => Err(HolochainError::new(&format!(
+                        "entry {} is not live",
+                        entry_hash
+                    ))),
+                }),
+            ActionResponse::Get(Err(err)) => Err(err),
+            _ => Err(HolochainError::new(&format!(
+                "entry {} not found",
+                entry_hash
+            ))),
+        },
+    )
+}
+
 /// given a runtime and a string (e.g. JSON serialized data), allocates bytes and encodes to memory
 /// returns a Result suitable to return directly from a zome API function
 pub fn runtime_allocate_encode_str(
@@ -305,7 +476,12 @@ pub mod tests {
     use self::wabt::Wat2Wasm;
     extern crate test_utils;
     use super::ZomeAPIFunction;
-    use instance::tests::{test_context_and_logger, test_instance, TestLogger};
+    use hash_table::entry::tests::test_type;
+    use holochain_dna::{zome::entry_types::EntryType, Dna};
+    use instance::{
+        tests::{test_context_and_logger, test_instance, TestLogger},
+        Instance,
+    };
     use nucleus::{
         ribosome::api::{call, Runtime},
         FunctionCall,
@@ -403,24 +579,48 @@ pub mod tests {
             .to_vec()
     }
 
+    /// builds dna for testing zome API functions against the given wasm
+    /// the zome declares the test entry type so that test entries pass entry type validation
+    pub fn test_zome_api_function_dna(wasm: &[u8]) -> Dna {
+        let mut dna = test_utils::create_test_dna_with_wasm(
+            "test_zome",
+            ReservedCapabilityNames::MissingNo.as_str(),
+            wasm.to_vec(),
+        );
+        let mut entry_type = EntryType::new();
+        entry_type.name = test_type();
+        dna.zomes[0].entry_types.push(entry_type);
+        dna
+    }
+
     /// given a canonical zome API function name and args as bytes:
     /// - builds wasm with test_zome_api_function_wasm
-    /// - builds dna and test instance
+    /// - builds dna with test_zome_api_function_dna and test instance
     /// - calls the zome API function with passed bytes argument using the instance runtime
     /// - returns the runtime after the call completes
     pub fn test_zome_api_function_runtime(
         canonical_name: &str,
         args_bytes: Vec<u8>,
+    ) -> (Runtime, Arc<Mutex<TestLogger>>) {
+        let wasm = test_zome_api_function_wasm(canonical_name);
+        let dna = test_zome_api_function_dna(&wasm);
+        let instance = test_instance(dna);
+
+        test_zome_api_function_call(&instance, &wasm, args_bytes)
+    }
+
+    /// as test_zome_api_function_runtime but calls against an existing instance
+    /// useful to prepare the instance state (e.g. commit entries) before the call
+    pub fn test_zome_api_function_call(
+        instance: &Instance,
+        wasm: &[u8],
+        args_bytes: Vec<u8>,
     ) -> (Runtime, Arc<Mutex<TestLogger>>) {
         let zome_name = "test_zome";
         let capability = ReservedCapabilityNames::MissingNo.as_str().to_string();
         let function_name = "test";
         let parameters = "";
 
-        let wasm = test_zome_api_function_wasm(canonical_name);
-        let dna =
-            test_utils::create_test_dna_with_wasm(zome_name.into(), &capability, wasm.clone());
-        let instance = test_instance(dna);
         let (context, logger) = test_context_and_logger("joan");
 
         let fc = FunctionCall::new(&zome_name, &capability, &function_name, &parameters);
@@ -430,7 +630,7 @@ pub mod tests {
                 context,
                 &instance.action_channel(),
                 &instance.observer_channel(),
-                wasm.clone(),
+                wasm.to_vec(),
                 &fc,
                 Some(args_bytes),
             ).expect("test should be callable"),
@@ -453,6 +653,30 @@ pub mod tests {
             ZomeAPIFunction::Get,
             ZomeAPIFunction::from_str("get").unwrap(),
         );
+        assert_eq!(
+            ZomeAPIFunction::Link,
+            ZomeAPIFunction::from_str("link").unwrap(),
+        );
+        assert_eq!(
+            ZomeAPIFunction::GetLinks,
+            ZomeAPIFunction::from_str("get_links").unwrap(),
+        );
+        assert_eq!(
+            ZomeAPIFunction::RemoveLink,
+            ZomeAPIFunction::from_str("remove_link").unwrap(),
+        );
+        assert_eq!(
+            ZomeAPIFunction::UpdateEntry,
+            ZomeAPIFunction::from_str("update_entry").unwrap(),
+        );
+        assert_eq!(
+            ZomeAPIFunction::RemoveEntry,
+            ZomeAPIFunction::from_str("remove_entry").unwrap(),
+        );
+        assert_eq!(
+            ZomeAPIFunction::Call,
+            ZomeAPIFunction::from_str("call").unwrap(),
+        );
 
         assert_eq!(
             "Cannot convert string to ZomeAPIFunction",
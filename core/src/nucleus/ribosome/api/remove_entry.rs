@@ -0,0 +1,198 @@
+use action::{Action, ActionWrapper};
+use agent::state::ActionResponse;
+use nucleus::ribosome::{
+    api::{
+        dispatch_and_wait_for_response, get_live_entry_pair, runtime_allocate_encode_str,
+        runtime_args_to_utf8, HcApiReturnCode, Runtime,
+    },
+    callback::{validate_del::validate_del, CallbackParams, CallbackResult},
+};
+use serde_json;
+use std::error::Error;
+use wasmi::{RuntimeArgs, RuntimeValue, Trap};
+
+/// Struct for input data received when RemoveEntry API function is invoked
+#[derive(Deserialize, Default, Debug, Serialize)]
+struct RemoveEntryArgs {
+    hash: String,
+}
+
+/// HcApiFuncIndex::REMOVE_ENTRY function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: r#"{"hash":"Qm..."}"#
+/// only a LIVE entry can be removed, a MODIFIED, DELETED or REJECTED one can't be removed again
+/// the entry must pass the validate_del callback, it is then marked DELETED and a deletion record
+/// is pushed to the source chain
+/// Returns the hash of the deletion record as per commit or an HcApiReturnCode as I32 on failure
+pub fn invoke_remove_entry(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    // deserialize args
+    let args_str = runtime_args_to_utf8(&runtime, &args);
+    let remove_input: RemoveEntryArgs = match serde_json::from_str(&args_str) {
+        Ok(remove_input) => remove_input,
+        // Exit on error
+        Err(_) => {
+            // Return Error code in i32 format
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorSerdeJson as i32,
+            )));
+        }
+    };
+
+    let pair = match get_live_entry_pair(runtime, &remove_input.hash) {
+        Ok(pair) => pair,
+        Err(err) => {
+            let _ = runtime
+                .context
+                .log(&format!("could not remove entry: {}", err.description()));
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )));
+        }
+    };
+
+    if let CallbackResult::Fail(reason) = validate_del(
+        &runtime.action_channel,
+        &runtime.observer_channel,
+        &runtime.function_call.zome,
+        &CallbackParams::ValidateDel(pair.entry().clone()),
+    ) {
+        let _ = runtime.context.log(&format!(
+            "rejected removal of entry {}: {}",
+            remove_input.hash, reason
+        ));
+        return Ok(Some(RuntimeValue::I32(
+            HcApiReturnCode::ErrorCallbackResult as i32,
+        )));
+    }
+    // anything other than a fail means we should remove the entry
+
+    let action_result = dispatch_and_wait_for_response(
+        runtime,
+        ActionWrapper::new(Action::RemoveEntry(remove_input.hash)),
+    );
+
+    match action_result {
+        ActionResponse::RemoveEntry(Ok(_)) => {
+            // serialize, allocate and encode result
+            runtime_allocate_encode_str(runtime, &action_result.to_json())
+        }
+        _ => {
+            let _ = runtime
+                .context
+                .log(&format!("remove_entry failed: {}", action_result.to_json()));
+            Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoveEntryArgs;
+    use action::{Action, ActionWrapper};
+    use agent::state::EntryUpdate;
+    use chain::DELETION_ENTRY_TYPE;
+    use hash_table::{
+        entry::{
+            tests::{test_entry, test_type},
+            Entry,
+        },
+        status::CRUDStatus,
+        HashTable,
+    };
+    use nucleus::ribosome::api::{
+        tests::test_zome_api_function_call, update_entry::tests::test_crud_instance,
+    };
+    use serde_json;
+
+    /// remove entry args for an entry hash
+    fn remove_args_bytes(hash: &str) -> Vec<u8> {
+        let args = RemoveEntryArgs { hash: hash.into() };
+        serde_json::to_string(&args)
+            .expect("args should serialize")
+            .into_bytes()
+    }
+
+    #[test]
+    /// test that we can remove an entry and get the hash of the deletion record
+    fn test_remove_entry_round_trip() {
+        let (instance, wasm) = test_crud_instance("remove_entry");
+        let pair = instance.state().agent().top_pair().unwrap();
+
+        let (runtime, _) =
+            test_zome_api_function_call(&instance, &wasm, remove_args_bytes(&test_entry().hash()));
+
+        let agent = instance.state().agent();
+        let deletion = agent.top_pair().unwrap();
+        assert_eq!(DELETION_ENTRY_TYPE, deletion.entry().entry_type());
+        assert_eq!(
+            runtime.result,
+            format!(r#"{{"hash":"{}"}}"#, deletion.header().entry()) + "\u{0}",
+        );
+        assert_eq!(
+            Ok(CRUDStatus::DELETED),
+            agent.chain().table().read().unwrap().get_status(&pair),
+        );
+
+        // removed entries can't be removed again
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, remove_args_bytes(&test_entry().hash()));
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("is not live")));
+        assert_eq!(Some(deletion), instance.state().agent().top_pair());
+    }
+
+    #[test]
+    /// test that entries that aren't on the chain can't be removed
+    fn test_remove_entry_missing() {
+        let (instance, wasm) = test_crud_instance("remove_entry");
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, remove_args_bytes("xxx"));
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("entry xxx not found")));
+    }
+
+    #[test]
+    /// test that old versions of updated entries can't be removed
+    fn test_remove_entry_modified() {
+        let (mut instance, wasm) = test_crud_instance("remove_entry");
+        let pair = instance.state().agent().top_pair().unwrap();
+        instance.dispatch_and_wait(ActionWrapper::new(Action::UpdateEntry(EntryUpdate::new(
+            &test_entry().hash(),
+            &Entry::new(&test_type(), "new content"),
+        ))));
+        let top = instance.state().agent().top_pair();
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, remove_args_bytes(&test_entry().hash()));
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("is not live")));
+
+        let agent = instance.state().agent();
+        assert_eq!(top, agent.top_pair());
+        assert_eq!(
+            Ok(CRUDStatus::MODIFIED),
+            agent.chain().table().read().unwrap().get_status(&pair),
+        );
+    }
+}
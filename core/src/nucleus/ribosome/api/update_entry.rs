@@ -0,0 +1,290 @@
+use action::{Action, ActionWrapper};
+use agent::state::{ActionResponse, EntryUpdate};
+use hash_table::entry::Entry;
+use nucleus::{
+    ribosome::{
+        api::{
+            commit::{reject, validation_error},
+            dispatch_and_wait_for_response, get_live_entry_pair, runtime_allocate_encode_str,
+            runtime_args_to_utf8, HcApiReturnCode, Runtime,
+        },
+        callback::{validate_mod::validate_mod, CallbackParams, CallbackResult},
+    },
+    validate_entry_and_wait_for_result, EntrySubmission,
+};
+use serde_json;
+use std::error::Error;
+use wasmi::{RuntimeArgs, RuntimeValue, Trap};
+
+/// Struct for input data received when UpdateEntry API function is invoked
+#[derive(Deserialize, Default, Debug, Serialize)]
+struct UpdateEntryArgs {
+    old_hash: String,
+    new_content: String,
+}
+
+/// HcApiFuncIndex::UPDATE_ENTRY function code
+/// args: [0] encoded MemoryAllocation as u32
+/// expected complex argument: r#"{"old_hash":"Qm...","new_content":"hello"}"#
+/// only a LIVE entry can be updated, a MODIFIED, DELETED or REJECTED one can't be updated again
+/// the new version has the entry type of the old version, it must pass the validation WASM of
+/// the entry type and the validate_mod callback, otherwise it is recorded as REJECTED
+/// the old version is marked MODIFIED with a crud-link to the new version
+/// Returns the hash of the new version as per commit or an HcApiReturnCode as I32 on failure
+pub fn invoke_update_entry(
+    runtime: &mut Runtime,
+    args: &RuntimeArgs,
+) -> Result<Option<RuntimeValue>, Trap> {
+    // deserialize args
+    let args_str = runtime_args_to_utf8(&runtime, &args);
+    let update_input: UpdateEntryArgs = match serde_json::from_str(&args_str) {
+        Ok(update_input) => update_input,
+        // Exit on error
+        Err(_) => {
+            // Return Error code in i32 format
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorSerdeJson as i32,
+            )));
+        }
+    };
+
+    let old_pair = match get_live_entry_pair(runtime, &update_input.old_hash) {
+        Ok(pair) => pair,
+        Err(err) => {
+            let _ = runtime
+                .context
+                .log(&format!("could not update entry: {}", err.description()));
+            return Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )));
+        }
+    };
+    let old_entry = old_pair.entry().clone();
+    let new_entry = Entry::new(&old_entry.entry_type(), &update_input.new_content);
+
+    // validate the new version against its entry type as per commit
+    if let Err(err) = validate_entry_and_wait_for_result(
+        EntrySubmission::new(
+            runtime.function_call.zome.clone(),
+            old_entry.entry_type(),
+            update_input.new_content,
+        ),
+        &runtime.action_channel,
+        &runtime.observer_channel,
+    ) {
+        return validation_error(runtime, new_entry, err);
+    }
+
+    if let CallbackResult::Fail(reason) = validate_mod(
+        &runtime.action_channel,
+        &runtime.observer_channel,
+        &runtime.function_call.zome,
+        &CallbackParams::ValidateMod(old_entry, new_entry.clone()),
+    ) {
+        return reject(
+            runtime,
+            new_entry,
+            HcApiReturnCode::ErrorCallbackResult,
+            &reason,
+        );
+    }
+    // anything other than a fail means we should update the entry
+
+    let action_result = dispatch_and_wait_for_response(
+        runtime,
+        ActionWrapper::new(Action::UpdateEntry(EntryUpdate::new(
+            &update_input.old_hash,
+            &new_entry,
+        ))),
+    );
+
+    match action_result {
+        ActionResponse::UpdateEntry(Ok(_)) => {
+            // serialize, allocate and encode result
+            runtime_allocate_encode_str(runtime, &action_result.to_json())
+        }
+        _ => {
+            let _ = runtime
+                .context
+                .log(&format!("update_entry failed: {}", action_result.to_json()));
+            Ok(Some(RuntimeValue::I32(
+                HcApiReturnCode::ErrorActionResult as i32,
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::UpdateEntryArgs;
+    use action::{Action, ActionWrapper};
+    use agent::state::ActionResponse;
+    use hash_table::{
+        entry::{
+            tests::{test_entry, test_type},
+            Entry,
+        },
+        status::CRUDStatus,
+        HashTable,
+    };
+    use holochain_dna::{wasm::DnaWasm, zome::entry_types::EntryType};
+    use instance::{tests::test_instance, Instance};
+    use nucleus::{
+        ribosome::api::tests::{
+            test_zome_api_function_call, test_zome_api_function_dna, test_zome_api_function_wasm,
+        },
+        tests::test_validation_wasm,
+    };
+    use serde_json;
+
+    /// update entry args for an entry hash and new content
+    fn update_args_bytes(old_hash: &str, new_content: &str) -> Vec<u8> {
+        let args = UpdateEntryArgs {
+            old_hash: old_hash.into(),
+            new_content: new_content.into(),
+        };
+        serde_json::to_string(&args)
+            .expect("args should serialize")
+            .into_bytes()
+    }
+
+    /// test instance for a zome API function with test_entry() committed and an "invalid" entry
+    /// type that always fails validation
+    pub fn test_crud_instance(canonical_name: &str) -> (Instance, Vec<u8>) {
+        let wasm = test_zome_api_function_wasm(canonical_name);
+        let mut dna = test_zome_api_function_dna(&wasm);
+        let mut entry_type = EntryType::new();
+        entry_type.name = "invalid".into();
+        entry_type.validation = DnaWasm {
+            code: test_validation_wasm(Some("invalid entry")),
+        };
+        dna.zomes[0].entry_types.push(entry_type);
+        let mut instance = test_instance(dna);
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(test_entry())));
+        (instance, wasm)
+    }
+
+    #[test]
+    /// test that we can update an entry and get the hash of the new version
+    fn test_update_entry_round_trip() {
+        let (instance, wasm) = test_crud_instance("update_entry");
+        let old_pair = instance.state().agent().top_pair().unwrap();
+        let new_entry = Entry::new(&test_type(), "new content");
+
+        let (runtime, _) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            update_args_bytes(&test_entry().hash(), "new content"),
+        );
+        assert_eq!(
+            runtime.result,
+            format!(r#"{{"hash":"{}"}}"#, new_entry.hash()) + "\u{0}",
+        );
+
+        let agent = instance.state().agent();
+        assert_eq!(
+            Some(&new_entry),
+            agent.top_pair().as_ref().map(|pair| pair.entry())
+        );
+        assert_eq!(
+            Ok(CRUDStatus::MODIFIED),
+            agent.chain().table().read().unwrap().get_status(&old_pair),
+        );
+
+        // the old version can't be updated again
+        let (runtime, logger) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            update_args_bytes(&test_entry().hash(), "other content"),
+        );
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("is not live")));
+    }
+
+    #[test]
+    /// test that entries that aren't on the chain can't be updated
+    fn test_update_entry_missing() {
+        let (instance, wasm) = test_crud_instance("update_entry");
+
+        let (runtime, logger) =
+            test_zome_api_function_call(&instance, &wasm, update_args_bytes("xxx", "content"));
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("entry xxx not found")));
+    }
+
+    #[test]
+    /// test that new versions failing the validation of their type are rejected
+    fn test_update_entry_invalid() {
+        let (mut instance, wasm) = test_crud_instance("update_entry");
+        let invalid = Entry::new("invalid", "old content");
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(invalid.clone())));
+        let top = instance.state().agent().top_pair();
+
+        let (runtime, logger) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            update_args_bytes(&invalid.hash(), "new content"),
+        );
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains("invalid entry")));
+
+        // the old version is untouched
+        let agent = instance.state().agent();
+        assert_eq!(top, agent.top_pair());
+        assert_eq!(
+            Ok(CRUDStatus::LIVE),
+            agent
+                .chain()
+                .table()
+                .read()
+                .unwrap()
+                .get_status(&top.unwrap()),
+        );
+    }
+
+    #[test]
+    /// test that removed entries can't be updated and nothing is validated for them
+    fn test_update_entry_not_live() {
+        let (mut instance, wasm) = test_crud_instance("update_entry");
+        let invalid = Entry::new("invalid", "old content");
+        instance.dispatch_and_wait(ActionWrapper::new(Action::Commit(invalid.clone())));
+        instance.dispatch_and_wait(ActionWrapper::new(Action::RemoveEntry(invalid.hash())));
+        let top = instance.state().agent().top_pair();
+
+        let (runtime, logger) = test_zome_api_function_call(
+            &instance,
+            &wasm,
+            update_args_bytes(&invalid.hash(), "new content"),
+        );
+        assert_eq!(runtime.result, "");
+        assert!(logger
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .any(|msg| msg.contains(&format!("entry {} is not live", invalid.hash()))));
+
+        // the new version is neither committed nor rejected
+        let agent = instance.state().agent();
+        assert_eq!(top, agent.top_pair());
+        assert!(!agent.actions().values().any(|response| match response {
+            ActionResponse::Reject(_) => true,
+            _ => false,
+        }));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod genesis;
 pub mod receive;
 pub mod validate_commit;
+pub mod validate_del;
+pub mod validate_mod;
 
 use action::ActionWrapper;
 use error::HolochainError;
@@ -10,7 +12,10 @@ use instance::Observer;
 use nucleus::{
     call_zome_and_wait_for_result,
     ribosome::{
-        callback::{genesis::genesis, receive::receive, validate_commit::validate_commit},
+        callback::{
+            genesis::genesis, receive::receive, validate_commit::validate_commit,
+            validate_del::validate_del, validate_mod::validate_mod,
+        },
         Defn,
     },
     FunctionCall,
@@ -32,6 +37,12 @@ pub enum Callback {
     /// validate_commit() -> bool
     ValidateCommit,
 
+    /// validate_mod() -> bool
+    ValidateMod,
+
+    /// validate_del() -> bool
+    ValidateDel,
+
     /// LifeCycle Capability
 
     /// genesis() -> bool
@@ -49,6 +60,8 @@ impl FromStr for Callback {
         match s {
             "genesis" => Ok(Callback::Genesis),
             "validate_commit" => Ok(Callback::ValidateCommit),
+            "validate_mod" => Ok(Callback::ValidateMod),
+            "validate_del" => Ok(Callback::ValidateDel),
             "receive" => Ok(Callback::Receive),
             "" => Ok(Callback::MissingNo),
             _ => Err("Cannot convert string to Callback"),
@@ -78,6 +91,8 @@ impl Callback {
             Callback::MissingNo => noop,
             Callback::Genesis => genesis,
             Callback::ValidateCommit => validate_commit,
+            Callback::ValidateMod => validate_mod,
+            Callback::ValidateDel => validate_del,
             // @TODO call this from somewhere
             // @see https://github.com/holochain/holochain-rust/issues/201
             Callback::Receive => receive,
@@ -91,6 +106,8 @@ impl Defn for Callback {
             Callback::MissingNo => "",
             Callback::Genesis => "genesis",
             Callback::ValidateCommit => "validate_commit",
+            Callback::ValidateMod => "validate_mod",
+            Callback::ValidateDel => "validate_del",
             Callback::Receive => "receive",
         }
     }
@@ -116,6 +133,12 @@ impl Defn for Callback {
             // @TODO needs a sensible capability
             // @see https://github.com/holochain/holochain-rust/issues/133
             Callback::ValidateCommit => ReservedCapabilityNames::MissingNo,
+            // @TODO needs a sensible capability
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            Callback::ValidateMod => ReservedCapabilityNames::MissingNo,
+            // @TODO needs a sensible capability
+            // @see https://github.com/holochain/holochain-rust/issues/133
+            Callback::ValidateDel => ReservedCapabilityNames::MissingNo,
             // @TODO call this from somewhere
             // @see https://github.com/holochain/holochain-rust/issues/201
             Callback::Receive => ReservedCapabilityNames::Communication,
@@ -127,6 +150,10 @@ impl Defn for Callback {
 pub enum CallbackParams {
     Genesis,
     ValidateCommit(Entry),
+    /// the old and new versions of an updated entry
+    ValidateMod(Entry, Entry),
+    /// the entry being removed
+    ValidateDel(Entry),
     // @TODO call this from somewhere
     // @see https://github.com/holochain/holochain-rust/issues/201
     Receive,
@@ -137,6 +164,10 @@ impl ToString for CallbackParams {
         match self {
             CallbackParams::Genesis => "".to_string(),
             CallbackParams::ValidateCommit(entry) => entry.to_json(),
+            CallbackParams::ValidateMod(old, new) => {
+                format!("{{\"old\":{},\"new\":{}}}", old.to_json(), new.to_json())
+            }
+            CallbackParams::ValidateDel(entry) => entry.to_json(),
             CallbackParams::Receive => "".to_string(),
         }
     }
@@ -302,6 +333,14 @@ pub mod tests {
             Callback::ValidateCommit,
             Callback::from_str("validate_commit").expect("string literal should be valid callback"),
         );
+        assert_eq!(
+            Callback::ValidateMod,
+            Callback::from_str("validate_mod").expect("string literal should be valid callback"),
+        );
+        assert_eq!(
+            Callback::ValidateDel,
+            Callback::from_str("validate_del").expect("string literal should be valid callback"),
+        );
         assert_eq!(
             Callback::Receive,
             Callback::from_str("receive").expect("string literal should be valid callback")
@@ -0,0 +1,96 @@
+use super::call;
+use action::ActionWrapper;
+use instance::Observer;
+use nucleus::ribosome::callback::{Callback, CallbackParams, CallbackResult};
+use std::sync::mpsc::Sender;
+
+pub fn validate_mod(
+    action_channel: &Sender<ActionWrapper>,
+    observer_channel: &Sender<Observer>,
+    zome: &str,
+    params: &CallbackParams,
+) -> CallbackResult {
+    call(
+        action_channel,
+        observer_channel,
+        zome,
+        &Callback::ValidateMod,
+        params,
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::validate_mod;
+    use hash_table::entry::tests::{test_entry, test_entry_b};
+    use nucleus::ribosome::{
+        callback::{tests::test_callback_instance, Callback, CallbackParams, CallbackResult},
+        Defn,
+    };
+
+    #[test]
+    /// test that the old and new versions are both passed to validate_mod
+    fn params() {
+        assert_eq!(
+            format!(
+                r#"{{"old":{},"new":{}}}"#,
+                test_entry().to_json(),
+                test_entry_b().to_json()
+            ),
+            CallbackParams::ValidateMod(test_entry(), test_entry_b()).to_string(),
+        );
+    }
+
+    #[test]
+    fn pass() {
+        let zome = "test_zome";
+        let instance = test_callback_instance(zome, Callback::ValidateMod.as_str(), 0);
+
+        let result = validate_mod(
+            &instance.action_channel(),
+            &instance.observer_channel(),
+            zome,
+            &CallbackParams::ValidateMod(test_entry(), test_entry_b()),
+        );
+
+        assert_eq!(CallbackResult::Pass, result);
+    }
+
+    #[test]
+    fn not_implemented() {
+        let zome = "test_zome";
+        let instance = test_callback_instance(
+            zome,
+            // anything other than ValidateMod is fine here
+            Callback::Genesis.as_str(),
+            0,
+        );
+
+        let result = validate_mod(
+            &instance.action_channel(),
+            &instance.observer_channel(),
+            zome,
+            &CallbackParams::ValidateMod(test_entry(), test_entry_b()),
+        );
+
+        assert_eq!(CallbackResult::NotImplemented, result);
+    }
+
+    #[test]
+    fn fail() {
+        let zome = "test_zome";
+        let instance = test_callback_instance(zome, Callback::ValidateMod.as_str(), 1);
+
+        let result = validate_mod(
+            &instance.action_channel(),
+            &instance.observer_channel(),
+            zome,
+            &CallbackParams::ValidateMod(test_entry(), test_entry_b()),
+        );
+
+        // @TODO how to get fail strings back out?
+        // @see https://github.com/holochain/holochain-rust/issues/205
+        assert_eq!(CallbackResult::Fail("{".to_string()), result);
+    }
+}
@@ -1,6 +1,6 @@
 use error::HolochainError;
 use holochain_dna::Dna;
-use nucleus::FunctionCall;
+use nucleus::{EntrySubmission, FunctionCall, LinkSubmission};
 use std::collections::HashMap;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -26,6 +26,12 @@ pub struct NucleusState {
     // @TODO should this use the standard ActionWrapper/ActionResponse format?
     // @see https://github.com/holochain/holochain-rust/issues/196
     pub ribosome_calls: HashMap<FunctionCall, Option<Result<String, HolochainError>>>,
+    // @TODO eventually drop stale validations
+    // @see https://github.com/holochain/holochain-rust/issues/166
+    pub validation_results: HashMap<EntrySubmission, Option<Result<(), HolochainError>>>,
+    // @TODO eventually drop stale validations
+    // @see https://github.com/holochain/holochain-rust/issues/166
+    pub link_validation_results: HashMap<LinkSubmission, Option<Result<(), HolochainError>>>,
 }
 
 impl NucleusState {
@@ -34,6 +40,8 @@ impl NucleusState {
             dna: None,
             status: NucleusStatus::New,
             ribosome_calls: HashMap::new(),
+            validation_results: HashMap::new(),
+            link_validation_results: HashMap::new(),
         }
     }
 
@@ -47,6 +55,28 @@ impl NucleusState {
         }
     }
 
+    /// the result of validating the submission, None until the validation has finished
+    pub fn validation_result(
+        &self,
+        submission: &EntrySubmission,
+    ) -> Option<Result<(), HolochainError>> {
+        match self.validation_results.get(submission) {
+            None => None,
+            Some(value) => value.clone(),
+        }
+    }
+
+    /// the result of validating the link submission, None until the validation has finished
+    pub fn link_validation_result(
+        &self,
+        submission: &LinkSubmission,
+    ) -> Option<Result<(), HolochainError>> {
+        match self.link_validation_results.get(submission) {
+            None => None,
+            Some(value) => value.clone(),
+        }
+    }
+
     pub fn has_initialized(&self) -> bool {
         self.status == NucleusStatus::Initialized
     }
@@ -1,4 +1,5 @@
 use error::HolochainError;
+use nucleus::api_key::ApiKey;
 use state::State;
 
 /// trait that defines the persistence functionality that holochain_core requires
@@ -9,11 +10,21 @@ pub trait Persister: Send {
     // @see https://github.com/holochain/holochain-rust/issues/203
     fn save(&mut self, state: State);
     fn load(&self) -> Result<Option<State>, HolochainError>;
+    /// api keys live outside the state so that revoking a key can't be undone by loading a state
+    /// persisters that can't save api keys can't issue or revoke them either
+    fn save_api_keys(&mut self, _api_keys: Vec<ApiKey>) -> Result<(), HolochainError> {
+        Err(HolochainError::NotImplemented)
+    }
+    /// persisters that can't save api keys have none, so api-key membranes reject every call
+    fn load_api_keys(&self) -> Result<Vec<ApiKey>, HolochainError> {
+        Ok(Vec::new())
+    }
 }
 
 #[derive(Default, Clone, PartialEq)]
 pub struct SimplePersister {
     state: Option<State>,
+    api_keys: Vec<ApiKey>,
 }
 
 impl Persister for SimplePersister {
@@ -23,19 +34,32 @@ impl Persister for SimplePersister {
     fn load(&self) -> Result<Option<State>, HolochainError> {
         Ok(self.state.clone())
     }
+    fn save_api_keys(&mut self, api_keys: Vec<ApiKey>) -> Result<(), HolochainError> {
+        self.api_keys = api_keys;
+        Ok(())
+    }
+    fn load_api_keys(&self) -> Result<Vec<ApiKey>, HolochainError> {
+        Ok(self.api_keys.clone())
+    }
 }
 
 impl SimplePersister {
     pub fn new() -> Self {
-        SimplePersister { state: None }
+        SimplePersister {
+            state: None,
+            api_keys: Vec::new(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use action::{tests::test_action_wrapper_commit, ActionWrapper};
+    use action::{tests::test_action_wrapper_commit, Action, ActionWrapper};
+    use agent::keys::tests::test_keys_b;
+    use holochain_dna::Dna;
     use instance::tests::test_context;
+    use nucleus::api_key::tests::test_api_key;
     use std::sync::mpsc::channel;
 
     #[test]
@@ -43,6 +67,7 @@ mod tests {
         let store = SimplePersister::new();
 
         assert_eq!(store.load(), Ok(None));
+        assert_eq!(store.load_api_keys(), Ok(Vec::new()));
     }
 
     #[test]
@@ -66,4 +91,80 @@ mod tests {
 
         assert_eq!(store.load(), Ok(Some(new_state)));
     }
+
+    #[test]
+    /// test that a saved state doesn't hold the private key of keys the agent rotated to
+    fn can_roundtrip_without_rotated_private_key() {
+        let mut store = SimplePersister::new();
+        let context = test_context("jane");
+        let (sender, _receiver) = channel::<ActionWrapper>();
+        let (tx_observer, _observer) = channel::<::instance::Observer>();
+
+        let new_key = context
+            .add_rotation_keys(test_keys_b())
+            .expect("adding rotation keys shouldn't fail");
+        let mut state = State::new();
+        for action in &[
+            Action::InitApplication(Dna::new()),
+            Action::RotateKeys(new_key.clone()),
+        ] {
+            state = state.reduce(
+                context.clone(),
+                ActionWrapper::new(action.clone()),
+                &sender,
+                &tx_observer,
+            );
+        }
+        assert_eq!(Some(test_keys_b()), state.agent().keys());
+
+        store.save(state.clone());
+
+        let saved = store
+            .load()
+            .expect("loading the state shouldn't fail")
+            .expect("the state should be saved");
+        assert_eq!(saved, state);
+        let private_key = test_keys_b().private_key().to_b58();
+        let agent_actions = saved.agent().actions();
+        for action_wrapper in saved.history.iter().chain(agent_actions.keys()) {
+            assert!(!format!("{:?}", action_wrapper).contains(&private_key));
+            if let Action::RotateKeys(key) = action_wrapper.action() {
+                assert_eq!(&new_key, key);
+            }
+        }
+    }
+
+    #[test]
+    /// test that api keys round trip independently of the state
+    fn can_roundtrip_api_keys() {
+        let mut store = SimplePersister::new();
+        let api_keys = vec![test_api_key(), test_api_key()];
+
+        assert_eq!(store.save_api_keys(api_keys.clone()), Ok(()));
+        store.save(State::new());
+
+        assert_eq!(store.load_api_keys(), Ok(api_keys));
+    }
+
+    /// persister that only implements the required methods
+    struct StatePersister;
+
+    impl Persister for StatePersister {
+        fn save(&mut self, _state: State) {}
+        fn load(&self) -> Result<Option<State>, HolochainError> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    /// test that persisters without api key storage have no api keys and can't save any
+    fn default_api_keys() {
+        let mut store = StatePersister;
+
+        assert_eq!(store.load_api_keys(), Ok(Vec::new()));
+        assert_eq!(
+            store.save_api_keys(vec![test_api_key()]),
+            Err(HolochainError::NotImplemented)
+        );
+    }
 }
@@ -11,7 +11,9 @@
 //! use holochain_core_api::*;
 //! use holochain_dna::Dna;
 //! use holochain_agent::Agent;
+//! use std::collections::HashMap;
 //! use std::sync::{Arc, Mutex};
+//! use holochain_core::clock::SystemClock;
 //! use holochain_core::context::Context;
 //! use holochain_core::logger::SimpleLogger;
 //! use holochain_core::persister::SimplePersister;
@@ -28,6 +30,9 @@
 //!     agent: agent,
 //!     logger: Arc::new(Mutex::new(SimpleLogger {})),
 //!     persister: Arc::new(Mutex::new(SimplePersister::new())),
+//!     clock: Arc::new(SystemClock {}),
+//!     keys: None,
+//!     rotation_keys: Arc::new(Mutex::new(HashMap::new())),
 //! };
 //! let mut hc = Holochain::new(dna,Arc::new(context)).unwrap();
 //!
@@ -60,10 +65,12 @@ use holochain_core::{
     context::Context,
     error::HolochainError,
     instance::Instance,
-    nucleus::{call_and_wait_for_result, state::NucleusStatus, FunctionCall},
+    nucleus::{
+        api_key::ApiKey, call_and_wait_for_result, state::NucleusStatus, FunctionCall, Provenance,
+    },
     state::State,
 };
-use holochain_dna::Dna;
+use holochain_dna::{zome::capabilities::Membrane, Dna};
 use std::{
     sync::{mpsc::channel, Arc},
     time::Duration,
@@ -72,7 +79,6 @@ use std::{
 /// contains a Holochain application instance
 pub struct Holochain {
     instance: Instance,
-    #[allow(dead_code)]
     context: Arc<Context>,
     active: bool,
 }
@@ -139,23 +145,106 @@ impl Holochain {
         Ok(())
     }
 
-    /// call a function in a zome
+    /// call a function in a zome as the agent running the instance
+    /// capabilities with the ApiKey membrane can't be called without a key, see
+    /// call_with_provenance()
     pub fn call(
         &mut self,
         zome: &str,
         cap: &str,
         fn_name: &str,
         params: &str,
+    ) -> Result<String, HolochainError> {
+        self.call_with_provenance(zome, cap, fn_name, params, Provenance::Agent)
+    }
+
+    /// call a function in a zome on behalf of whoever the provenance says is calling
+    /// the call fails with MembraneRejected unless the provenance satisfies the membrane of the
+    /// capability, so untrusted clients should never be given Provenance::Agent
+    /// clients calling capabilities with the ApiKey membrane need a key from issue_api_key()
+    pub fn call_with_provenance(
+        &mut self,
+        zome: &str,
+        cap: &str,
+        fn_name: &str,
+        params: &str,
+        provenance: Provenance,
     ) -> Result<String, HolochainError> {
         if !self.active {
             return Err(HolochainError::InstanceNotActive);
         }
 
-        let call = FunctionCall::new(&zome, &cap, &fn_name, &params);
+        let call = FunctionCall::new(&zome, &cap, &fn_name, &params).with_provenance(provenance);
 
         call_and_wait_for_result(call, &mut self.instance)
     }
 
+    /// issue a new api key that lets clients call the functions of a capability of a zome
+    /// only capabilities with the ApiKey membrane take api keys
+    pub fn issue_api_key(&mut self, zome: &str, cap: &str) -> Result<ApiKey, HolochainError> {
+        let dna = self
+            .instance
+            .state()
+            .nucleus()
+            .dna()
+            .ok_or(HolochainError::DnaMissing)?;
+        let membrane = dna
+            .get_zome(zome)
+            .ok_or_else(|| HolochainError::ZomeNotFound(format!("Zome '{}' not found", zome)))?
+            .capabilities
+            .iter()
+            .find(|capability| capability.name == cap)
+            .map(|capability| capability.capability.membrane.clone())
+            .ok_or_else(|| {
+                HolochainError::CapabilityNotFound(format!(
+                    "Capability '{}' not found in Zome '{}'",
+                    cap, zome
+                ))
+            })?;
+        if membrane != Membrane::ApiKey {
+            return Err(HolochainError::new(&format!(
+                "Capability '{}' of Zome '{}' has the {:?} membrane and doesn't take api keys",
+                cap, zome, membrane
+            )));
+        }
+
+        let api_key = ApiKey::new(zome, cap)?;
+        let mut persister = self
+            .context
+            .persister
+            .lock()
+            .expect("owners of the persister Mutex shouldn't panic");
+        let mut api_keys = persister.load_api_keys()?;
+        api_keys.push(api_key.clone());
+        persister.save_api_keys(api_keys)?;
+        Ok(api_key)
+    }
+
+    /// the api keys that have been issued and not revoked
+    pub fn api_keys(&self) -> Result<Vec<ApiKey>, HolochainError> {
+        self.context
+            .persister
+            .lock()
+            .expect("owners of the persister Mutex shouldn't panic")
+            .load_api_keys()
+    }
+
+    /// revoke an issued api key, clients presenting the key can't make calls with it any more
+    pub fn revoke_api_key(&mut self, key: &str) -> Result<(), HolochainError> {
+        let mut persister = self
+            .context
+            .persister
+            .lock()
+            .expect("owners of the persister Mutex shouldn't panic");
+        let mut api_keys = persister.load_api_keys()?;
+        let issued = api_keys.len();
+        api_keys.retain(|api_key| api_key.key() != key);
+        if api_keys.len() == issued {
+            return Err(HolochainError::new("no such api key was issued"));
+        }
+        persister.save_api_keys(api_keys)
+    }
+
     /// checks to see if an instance is active
     pub fn active(&self) -> bool {
         self.active
@@ -172,11 +261,16 @@ mod tests {
     extern crate holochain_agent;
     use super::*;
     use holochain_core::{
+        clock::SystemClock,
         context::Context,
         nucleus::ribosome::{callback::Callback, Defn},
         persister::SimplePersister,
     };
-    use std::sync::{Arc, Mutex};
+    use holochain_dna::zome::{capabilities::Membrane, entry_types::EntryType};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
     use test_utils::{create_test_dna_with_wasm, create_test_dna_with_wat, create_wasm_from_file};
 
     // TODO: TestLogger duplicated in test_utils because:
@@ -191,6 +285,9 @@ mod tests {
                 agent: agent,
                 logger: logger.clone(),
                 persister: Arc::new(Mutex::new(SimplePersister::new())),
+                clock: Arc::new(SystemClock {}),
+                keys: None,
+                rotation_keys: Arc::new(Mutex::new(HashMap::new())),
             }),
             logger,
         )
@@ -351,6 +448,92 @@ mod tests {
         };
     }
 
+    #[test]
+    fn can_call_with_provenance() {
+        let mut dna = create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::ApiKey;
+        let (context, _) = test_context("bob");
+        let mut hc = Holochain::new(dna.clone(), context).unwrap();
+        hc.start().expect("couldn't start");
+
+        // the agent needs an api key too
+        match hc.call("test_zome", "test_cap", "main", "") {
+            Err(HolochainError::MembraneRejected(_)) => (),
+            result => panic!("unexpected result of a keyless call: {:?}", result),
+        }
+        let api_key = hc.issue_api_key("test_zome", "test_cap").unwrap();
+        assert_eq!(
+            Ok("1337".to_string()),
+            hc.call_with_provenance(
+                "test_zome",
+                "test_cap",
+                "main",
+                "",
+                Provenance::ApiKey(api_key.key()),
+            )
+        );
+        for provenance in vec![Provenance::ApiKey("key".into()), Provenance::Public] {
+            match hc.call_with_provenance("test_zome", "test_cap", "main", "", provenance) {
+                Err(HolochainError::MembraneRejected(_)) => (),
+                result => panic!("unexpected result of an untrusted call: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn can_issue_and_revoke_api_keys() {
+        let mut dna = create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::ApiKey;
+        let (context, _) = test_context("bob");
+        let mut hc = Holochain::new(dna.clone(), context.clone()).unwrap();
+        hc.start().expect("couldn't start");
+        assert_eq!(Ok(Vec::new()), hc.api_keys());
+
+        let api_key = hc.issue_api_key("test_zome", "test_cap").unwrap();
+        let other_key = hc.issue_api_key("test_zome", "test_cap").unwrap();
+        assert_eq!(api_key.zome(), "test_zome");
+        assert_eq!(api_key.capability(), "test_cap");
+        assert_eq!(Ok(vec![api_key.clone(), other_key.clone()]), hc.api_keys());
+        // issued keys are persisted
+        assert_eq!(
+            Ok(vec![api_key.clone(), other_key.clone()]),
+            context.persister.lock().unwrap().load_api_keys()
+        );
+
+        hc.revoke_api_key(&api_key.key()).unwrap();
+        assert_eq!(Ok(vec![other_key.clone()]), hc.api_keys());
+        assert!(hc.revoke_api_key(&api_key.key()).is_err());
+        match hc.call_with_provenance(
+            "test_zome",
+            "test_cap",
+            "main",
+            "",
+            Provenance::ApiKey(api_key.key()),
+        ) {
+            Err(HolochainError::MembraneRejected(_)) => (),
+            result => panic!("unexpected result of a revoked call: {:?}", result),
+        }
+        assert_eq!(
+            Ok("1337".to_string()),
+            hc.call_with_provenance(
+                "test_zome",
+                "test_cap",
+                "main",
+                "",
+                Provenance::ApiKey(other_key.key()),
+            )
+        );
+
+        // only existing capabilities with the ApiKey membrane take api keys
+        assert!(hc.issue_api_key("xxx", "test_cap").is_err());
+        assert!(hc.issue_api_key("test_zome", "xxx").is_err());
+        let mut dna = create_test_dna_with_wat("test_zome", "test_cap", None);
+        dna.zomes[0].capabilities[0].capability.membrane = Membrane::Public;
+        let (context, _) = test_context("bob");
+        let mut hc = Holochain::new(dna.clone(), context).unwrap();
+        assert!(hc.issue_api_key("test_zome", "test_cap").is_err());
+    }
+
     #[test]
     fn can_get_state() {
         let dna = Dna::new();
@@ -400,7 +583,11 @@ mod tests {
         let wasm = create_wasm_from_file(
             "wasm-test/commit/target/wasm32-unknown-unknown/debug/commit.wasm",
         );
-        let dna = create_test_dna_with_wasm("test_zome", "test_cap", wasm);
+        let mut dna = create_test_dna_with_wasm("test_zome", "test_cap", wasm);
+        // the committed entry type must be in the zome
+        let mut entry_type = EntryType::new();
+        entry_type.name = "post".into();
+        dna.zomes[0].entry_types.push(entry_type);
         let (context, _) = test_context("alex");
         let mut hc = Holochain::new(dna.clone(), context).unwrap();
 
@@ -425,7 +612,7 @@ mod tests {
         // Check in holochain instance's history that the commit event has been processed
         // @TODO don't use history length in tests
         // @see https://github.com/holochain/holochain-rust/issues/195
-        assert_eq!(hc.state().unwrap().history.len(), 9);
+        assert_eq!(hc.state().unwrap().history.len(), 11);
     }
 
     #[test]
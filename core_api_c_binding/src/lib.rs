@@ -9,8 +9,9 @@ use holochain_dna::Dna;
 use std::sync::Arc;
 
 use holochain_agent::Agent;
-use holochain_core::{logger::Logger, persister::SimplePersister};
+use holochain_core::{clock::SystemClock, logger::Logger, persister::SimplePersister};
 use std::{
+    collections::HashMap,
     ffi::{CStr, CString},
     os::raw::c_char,
     sync::Mutex,
@@ -31,6 +32,9 @@ pub unsafe extern "C" fn holochain_new(ptr: *mut Dna) -> *mut Holochain {
         agent,
         logger: Arc::new(Mutex::new(NullLogger {})),
         persister: Arc::new(Mutex::new(SimplePersister::new())),
+        clock: Arc::new(SystemClock {}),
+        keys: None,
+        rotation_keys: Arc::new(Mutex::new(HashMap::new())),
     });
 
     assert!(!ptr.is_null());
@@ -25,6 +25,7 @@ extern crate serde;
 #[macro_use]
 extern crate serde_json;
 extern crate base64;
+extern crate jsonschema;
 extern crate uuid;
 
 use std::hash::{Hash, Hasher};
@@ -44,6 +45,28 @@ fn _def_new_uuid() -> String {
     Uuid::new_v4().to_string()
 }
 
+/// Enum for the multihash algorithm every hash in a holochain application is made with.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Hash)]
+pub enum HashAlgorithm {
+    #[serde(rename = "sha2-256")]
+    Sha2256,
+    #[serde(rename = "sha2-512")]
+    Sha2512,
+    #[serde(rename = "sha3-256")]
+    Sha3256,
+    #[serde(rename = "sha3-512")]
+    Sha3512,
+    #[serde(rename = "blake2b-512")]
+    Blake2b512,
+}
+
+impl Default for HashAlgorithm {
+    /// Default hash algorithm is "sha2-256"
+    fn default() -> Self {
+        HashAlgorithm::Sha2256
+    }
+}
+
 /// Represents the top-level holochain dna object.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Dna {
@@ -67,6 +90,10 @@ pub struct Dna {
     #[serde(default)]
     pub dna_spec_version: String,
 
+    /// The multihash algorithm for headers, entries and metadata (sha2-256, sha3-256, etc.).
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+
     /// Any arbitrary application properties can be included in this object.
     #[serde(default = "_def_empty_object")]
     pub properties: serde_json::Value,
@@ -85,6 +112,7 @@ impl Default for Dna {
             version: String::from(""),
             uuid: _def_new_uuid(),
             dna_spec_version: String::from("2.0"),
+            hash_algorithm: HashAlgorithm::default(),
             properties: _def_empty_object(),
             zomes: Vec::new(),
         }
@@ -185,17 +213,25 @@ impl Dna {
         Some(capability)
     }
 
+    /// Return an EntryType of a Zome
+    pub fn get_entry_type(
+        &self,
+        zome_name: &str,
+        entry_type_name: &str,
+    ) -> Option<&zome::entry_types::EntryType> {
+        let zome = self.get_zome(zome_name)?;
+        zome.entry_types
+            .iter()
+            .find(|et| et.name == entry_type_name)
+    }
+
     /// Return a Zome's WASM bytecode for the validation of an entry
     pub fn get_validation_bytecode_for_entry_type(
         &self,
         zome_name: &str,
         entry_type_name: &str,
     ) -> Option<&wasm::DnaWasm> {
-        let zome = self.zomes.iter().find(|z| z.name() == zome_name)?;
-        let entry_type = zome
-            .entry_types
-            .iter()
-            .find(|et| et.name == entry_type_name)?;
+        let entry_type = self.get_entry_type(zome_name, entry_type_name)?;
         Some(&entry_type.validation)
     }
 }
@@ -256,6 +292,7 @@ pub mod tests {
                 "version": "test",
                 "uuid": "00000000-0000-0000-0000-000000000000",
                 "dna_spec_version": "2.0",
+                "hash_algorithm": "sha3-256",
                 "properties": {
                     "test": "test"
                 },
@@ -369,6 +406,68 @@ pub mod tests {
         assert!(dna.uuid.len() > 0);
     }
 
+    #[test]
+    fn parse_hash_algorithm() {
+        assert_eq!(HashAlgorithm::Sha2256, Dna::new().hash_algorithm);
+
+        let dna = Dna::new_from_json(
+            r#"{
+                "hash_algorithm": "blake2b-512"
+            }"#,
+        ).unwrap();
+
+        assert_eq!(HashAlgorithm::Blake2b512, dna.hash_algorithm);
+
+        assert!(Dna::new_from_json(r#"{"hash_algorithm": "md5"}"#).is_err());
+    }
+
+    #[test]
+    fn parse_entry_schema() {
+        let dna = Dna::new_from_json(
+            r#"{
+                "zomes": [
+                    {
+                        "name": "zome",
+                        "entry_types": [
+                            {
+                                "name": "post",
+                                "schema": { "type": "string" }
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        ).unwrap();
+
+        let entry_type = dna
+            .get_entry_type("zome", "post")
+            .expect("the entry type should be in the zome");
+        assert_eq!(
+            Ok(()),
+            entry_type.schema.as_ref().unwrap().validate(r#""hello""#)
+        );
+        assert_eq!(None, dna.get_entry_type("zome", "comment"));
+        assert_eq!(None, dna.get_entry_type("other zome", "post"));
+
+        // dna with an invalid schema doesn't load
+        let result = Dna::new_from_json(
+            r#"{
+                "zomes": [
+                    {
+                        "name": "zome",
+                        "entry_types": [
+                            {
+                                "name": "post",
+                                "schema": { "type": "strng" }
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn parse_with_defaults_zome() {
         let dna = Dna::new_from_json(
@@ -1,5 +1,14 @@
 //! holochain_dna::zome::entry_types is a set of structs for working with holochain dna.
 
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::Arc,
+};
 use wasm::DnaWasm;
 
 /// Enum for Zome EntryType "sharing" property.
@@ -54,6 +63,89 @@ impl LinksTo {
     }
 }
 
+/// A JSON Schema that the content of every entry of an entry type must conform to.
+/// The schema itself is checked when it is built or deserialized, so a dna with an invalid
+/// schema fails to load. It is compiled once then, not for every entry that is validated.
+#[derive(Clone)]
+pub struct EntrySchema {
+    schema: serde_json::Value,
+    compiled: Arc<JSONSchema>,
+}
+
+impl EntrySchema {
+    /// Build an EntrySchema from a JSON Schema, returns why the schema is invalid on failure.
+    pub fn new(schema: serde_json::Value) -> Result<Self, String> {
+        let compiled = JSONSchema::compile(&schema)
+            .map_err(|error| format!("invalid entry schema: {}", error))?;
+        Ok(EntrySchema {
+            schema,
+            compiled: Arc::new(compiled),
+        })
+    }
+
+    /// Return the JSON Schema.
+    pub fn schema(&self) -> &serde_json::Value {
+        &self.schema
+    }
+
+    /// Check that entry content (a JSON string) conforms to the schema,
+    /// returns every way it doesn't on failure.
+    pub fn validate(&self, content: &str) -> Result<(), String> {
+        let content: serde_json::Value = serde_json::from_str(content)
+            .map_err(|error| format!("entry content is not valid JSON: {}", error))?;
+        self.compiled.validate(&content).map_err(|errors| {
+            errors
+                .map(|error| format!("{} at '{}'", error, error.instance_path))
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+    }
+}
+
+/// The compiled schema is never mutated once it is built, so a panic can't leave it broken.
+impl UnwindSafe for EntrySchema {}
+impl RefUnwindSafe for EntrySchema {}
+
+impl fmt::Debug for EntrySchema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EntrySchema({})", self.schema)
+    }
+}
+
+impl PartialEq for EntrySchema {
+    /// Schemas are equal if they are the same JSON Schema, however they were compiled.
+    fn eq(&self, other: &EntrySchema) -> bool {
+        self.schema == other.schema
+    }
+}
+
+impl Serialize for EntrySchema {
+    /// Only the JSON Schema is serialized, it is compiled again when it is deserialized.
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.schema.serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntrySchema {
+    /// Only valid JSON Schemas deserialize.
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let schema = serde_json::Value::deserialize(d)?;
+        EntrySchema::new(schema).map_err(::serde::de::Error::custom)
+    }
+}
+
+impl Hash for EntrySchema {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.schema.to_string().hash(state);
+    }
+}
+
 /// Represents an individual object in the "zome" "entry_types" array.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Hash)]
 pub struct EntryType {
@@ -76,6 +168,10 @@ pub struct EntryType {
     /// An array of entry_types associated with this zome.
     #[serde(default)]
     pub links_to: Vec<LinksTo>,
+
+    /// JSON Schema for the content of this entry_type, checked before the validation code runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<EntrySchema>,
 }
 
 impl Default for EntryType {
@@ -87,6 +183,7 @@ impl Default for EntryType {
             sharing: Sharing::Public,
             validation: DnaWasm::new(),
             links_to: Vec::new(),
+            schema: None,
         }
     }
 }
@@ -140,4 +237,70 @@ mod tests {
 
         assert_eq!(fixture, entry);
     }
+
+    #[test]
+    fn schema_round_trip() {
+        let fixture: EntryType = serde_json::from_str(
+            r#"{
+                "name": "post",
+                "schema": {
+                    "type": "object",
+                    "properties": {
+                        "title": { "type": "string" }
+                    },
+                    "required": ["title"]
+                }
+            }"#,
+        ).unwrap();
+
+        let schema = fixture
+            .schema
+            .clone()
+            .expect("the schema should deserialize");
+        assert_eq!("object", schema.schema()["type"]);
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        assert_eq!(fixture, serde_json::from_str::<EntryType>(&json).unwrap());
+
+        // entry types without a schema serialize as they did before schemas existed
+        assert!(!serde_json::to_string(&EntryType::new())
+            .unwrap()
+            .contains("schema"));
+    }
+
+    #[test]
+    fn invalid_schema() {
+        assert!(EntrySchema::new(json!({ "type": "strng" })).is_err());
+
+        let result: Result<EntryType, _> = serde_json::from_str(
+            r#"{
+                "name": "post",
+                "schema": { "type": 5 }
+            }"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn schema_validate() {
+        let schema = EntrySchema::new(json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" }
+            },
+            "required": ["title"]
+        })).unwrap();
+
+        assert_eq!(Ok(()), schema.validate(r#"{"title":"hello"}"#));
+
+        let error = schema.validate(r#"{"title":1}"#).unwrap_err();
+        assert!(error.contains("/title"));
+        assert!(schema.validate(r#"{}"#).is_err());
+        assert!(schema.validate("not json").is_err());
+
+        // copies of the schema validate with the schema compiled when it was built
+        let copy = schema.clone();
+        assert!(Arc::ptr_eq(&schema.compiled, &copy.compiled));
+        assert_eq!(Ok(()), copy.validate(r#"{"title":"hello"}"#));
+    }
 }
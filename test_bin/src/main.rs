@@ -4,10 +4,13 @@ extern crate holochain_core_api;
 extern crate holochain_dna;
 
 use holochain_agent::Agent;
-use holochain_core::{context::Context, logger::SimpleLogger, persister::SimplePersister};
+use holochain_core::{
+    clock::SystemClock, context::Context, logger::SimpleLogger, persister::SimplePersister,
+};
 use holochain_core_api::*;
 use holochain_dna::Dna;
 use std::{
+    collections::HashMap,
     env,
     sync::{Arc, Mutex},
 };
@@ -41,6 +44,9 @@ fn main() {
         agent,
         logger: Arc::new(Mutex::new(SimpleLogger {})),
         persister: Arc::new(Mutex::new(SimplePersister::new())),
+        clock: Arc::new(SystemClock {}),
+        keys: None,
+        rotation_keys: Arc::new(Mutex::new(HashMap::new())),
     };
     let mut hc = Holochain::new(dna, Arc::new(context)).unwrap();
     println!("Created a new instance with identity: {}", identity);
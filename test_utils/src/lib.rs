@@ -4,14 +4,16 @@ extern crate holochain_dna;
 extern crate wabt;
 
 use holochain_agent::Agent;
-use holochain_core::{context::Context, logger::Logger, persister::SimplePersister};
+use holochain_core::{
+    clock::SystemClock, context::Context, logger::Logger, persister::SimplePersister,
+};
 use holochain_dna::{
     wasm::DnaWasm,
     zome::{capabilities::Capability, Config, Zome},
     Dna,
 };
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{hash_map::DefaultHasher, HashMap},
     fmt,
     fs::File,
     hash::{Hash, Hasher},
@@ -110,6 +112,9 @@ pub fn test_context_and_logger(agent_name: &str) -> (Arc<Context>, Arc<Mutex<Tes
             agent,
             logger: logger.clone(),
             persister: Arc::new(Mutex::new(SimplePersister::new())),
+            clock: Arc::new(SystemClock {}),
+            keys: None,
+            rotation_keys: Arc::new(Mutex::new(HashMap::new())),
         }),
         logger,
     )
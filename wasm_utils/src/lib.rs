@@ -20,6 +20,8 @@ pub enum HcApiReturnCode {
     ErrorPageOverflow = 3 << 16,
     ErrorActionResult = 4 << 16,
     ErrorCallbackResult = 5 << 16,
+    ErrorValidationResult = 6 << 16,
+    ErrorEntryTypeNotFound = 7 << 16,
 }
 
 //pub fn decode_error(encoded_allocation: u32) -> HcApiReturnCode {
@@ -34,6 +36,9 @@ pub fn encode_error(offset: u16) -> HcApiReturnCode {
         2 => HcApiReturnCode::ErrorSerdeJson,
         3 => HcApiReturnCode::ErrorPageOverflow,
         4 => HcApiReturnCode::ErrorActionResult,
+        5 => HcApiReturnCode::ErrorCallbackResult,
+        6 => HcApiReturnCode::ErrorValidationResult,
+        7 => HcApiReturnCode::ErrorEntryTypeNotFound,
         1 | _ => HcApiReturnCode::Error,
     }
 }
@@ -232,6 +237,18 @@ pub mod tests {
         assert_eq!(super::encode_error(3), HcApiReturnCode::ErrorPageOverflow);
 
         assert_eq!(super::encode_error(4), HcApiReturnCode::ErrorActionResult);
+
+        assert_eq!(super::encode_error(5), HcApiReturnCode::ErrorCallbackResult);
+
+        assert_eq!(
+            super::encode_error(6),
+            HcApiReturnCode::ErrorValidationResult
+        );
+
+        assert_eq!(
+            super::encode_error(7),
+            HcApiReturnCode::ErrorEntryTypeNotFound
+        );
     }
 
     #[test]